@@ -9,6 +9,9 @@ use core::convert::TryFrom;
 #[cfg(feature = "alloc")]
 use alloc::{boxed::Box, string::String};
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
 const SATS_IN_BTC: u64 = 100_000_000;
 const MAX_MONEY_SAT: u64 = 21_000_000 * SATS_IN_BTC;
 const MAX_MONEY_MSAT: u64 = MAX_MONEY_SAT * 1000;
@@ -24,11 +27,12 @@ const MAX_MONEY_MSAT: u64 = MAX_MONEY_SAT * 1000;
 /// This type guarantees that the amount stays less than or equal to 21 million bitcoins.
 /// However `unsafe` code **must not** rely on this, at least for now.
 /// This implies that arithmetic operations always panic on overflow.
+/// Use the `checked_*` or `saturating_*` methods if the values are untrusted.
 ///
 /// ## `Display` implementation
 ///
 /// To avoid confusion, the amount is displayed with ` msat` suffix - e.g. `42 msat`.
-/// No other representations are supported yet, feel free to contribute!
+/// Use [`display_in`](Amount::display_in) to display the amount in other denominations.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct Amount(u64);
 
@@ -65,7 +69,7 @@ impl Amount {
     #[inline]
     pub fn from_msat(msat: u64) -> Result<Self, OverflowError> {
         if msat > MAX_MONEY_MSAT {
-            Err(OverflowError { amount: msat, denomination: "millisatoshis", })
+            Err(OverflowError { amount: msat, denomination: "millisatoshis", negative: false, })
         } else {
             Ok(Amount(msat))
         }
@@ -88,7 +92,7 @@ impl Amount {
     #[inline]
     pub fn from_sat(sat: u64) -> Result<Self, OverflowError> {
         if sat > MAX_MONEY_SAT {
-            Err(OverflowError { amount: sat, denomination: "satoshis", })
+            Err(OverflowError { amount: sat, denomination: "satoshis", negative: false, })
         } else {
             Ok(Amount(sat * 1000))
         }
@@ -124,7 +128,7 @@ impl Amount {
         if self.0 % 1000 == 0 {
             Ok(self.0 / 1000)
         } else {
-            Err(FractionError { amount: self.0, })
+            Err(FractionError { amount: self.0, negative: false, })
         }
     }
 
@@ -167,17 +171,537 @@ impl Amount {
         (self.0 + 500) / 1000
     }
 
-    /// Internal monomorphic parsing method.
+    /// Adds two amounts, returning `None` if the result exceeds the supply cap.
     ///
-    /// This should improve codegen without requiring allocations.
-    fn parse_raw(mut s: &str) -> Result<Self, ParseErrorInner> {
-        if s.ends_with(" msat") {
-            s = &s[..(s.len() - 5)];
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_SAT.checked_add(Amount::ONE_MSAT).unwrap().to_msat(), 1001);
+    /// assert!(Amount::MAX.checked_add(Amount::ONE_MSAT).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_add(self, rhs: Amount) -> Option<Amount> {
+        // Can't overflow u64 because both are at most MAX_MONEY_MSAT
+        let sum = self.0 + rhs.0;
+        if sum <= MAX_MONEY_MSAT {
+            Some(Amount(sum))
+        } else {
+            None
+        }
+    }
+
+    /// Subtracts `rhs` from `self`, returning `None` if the result would be negative.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_SAT.checked_sub(Amount::ONE_MSAT).unwrap().to_msat(), 999);
+    /// assert!(Amount::ZERO.checked_sub(Amount::ONE_MSAT).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_sub(self, rhs: Amount) -> Option<Amount> {
+        match self.0.checked_sub(rhs.0) {
+            Some(difference) => Some(Amount(difference)),
+            None => None,
+        }
+    }
+
+    /// Multiplies the amount by an integer, returning `None` if the result exceeds the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_SAT.checked_mul(42).unwrap().to_msat(), 42_000);
+    /// assert!(Amount::MAX.checked_mul(2).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_mul(self, rhs: u64) -> Option<Amount> {
+        match self.0.checked_mul(rhs) {
+            Some(product) if product <= MAX_MONEY_MSAT => Some(Amount(product)),
+            _ => None,
+        }
+    }
+
+    /// Divides the amount by an integer rounding down, returning `None` if `rhs` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_SAT.checked_div(3).unwrap().to_msat(), 333);
+    /// assert!(Amount::ONE_SAT.checked_div(0).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_div(self, rhs: u64) -> Option<Amount> {
+        match self.0.checked_div(rhs) {
+            Some(quotient) => Some(Amount(quotient)),
+            None => None,
+        }
+    }
+
+    /// Adds two amounts, returning [`Amount::MAX`] if the result exceeds the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::MAX.saturating_add(Amount::ONE_SAT), Amount::MAX);
+    /// ```
+    #[inline]
+    pub const fn saturating_add(self, rhs: Amount) -> Amount {
+        match self.checked_add(rhs) {
+            Some(sum) => sum,
+            None => Amount::MAX,
+        }
+    }
+
+    /// Subtracts `rhs` from `self`, returning [`Amount::ZERO`] if the result would be negative.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_MSAT.saturating_sub(Amount::ONE_SAT), Amount::ZERO);
+    /// ```
+    #[inline]
+    pub const fn saturating_sub(self, rhs: Amount) -> Amount {
+        Amount(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Computes the absolute difference between two amounts.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::ONE_MSAT.abs_diff(Amount::ONE_SAT).to_msat(), 999);
+    /// assert_eq!(Amount::ONE_SAT.abs_diff(Amount::ONE_MSAT).to_msat(), 999);
+    /// ```
+    #[inline]
+    pub const fn abs_diff(self, rhs: Amount) -> Amount {
+        if self.0 > rhs.0 {
+            Amount(self.0 - rhs.0)
+        } else {
+            Amount(rhs.0 - self.0)
+        }
+    }
+
+    /// Splits the amount into `parts` parts that differ by at most one millisatoshi.
+    ///
+    /// The returned iterator yields exactly `parts` amounts which sum to `self`.
+    /// Larger parts come first.
+    /// Use [`split_even_with_min`](Amount::split_even_with_min) to avoid parts that are too small
+    /// to be sent.
+    ///
+    /// ## Panics
+    ///
+    /// This method panics if `parts` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// let amount = Amount::from_msat(1_000).unwrap();
+    /// let parts = amount.split_even(3).map(Amount::to_msat).collect::<Vec<_>>();
+    /// assert_eq!(parts, [334, 333, 333]);
+    /// ```
+    #[inline]
+    pub fn split_even(self, parts: u64) -> SplitEven {
+        assert_ne!(parts, 0, "attempt to split an amount into zero parts");
+
+        SplitEven {
+            amount: self.0,
+            parts,
+            index: 0,
+        }
+    }
+
+    /// Splits the amount into parts proportional to `weights`.
+    ///
+    /// The returned iterator yields one amount for each weight and the amounts sum to `self`.
+    /// Each part is rounded down or up by less than one millisatoshi so that no millisatoshis
+    /// are lost or invented.
+    /// Parts for zero weights are always zero.
+    /// Use [`split_weighted_with_min`](Amount::split_weighted_with_min) to avoid parts that are
+    /// too small to be sent.
+    ///
+    /// ## Panics
+    ///
+    /// This method panics if all weights are zero (including the case when there are none).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// let amount = Amount::from_msat(1_000).unwrap();
+    /// let parts = amount.split_weighted(&[1, 0, 2]).map(Amount::to_msat).collect::<Vec<_>>();
+    /// assert_eq!(parts, [333, 0, 667]);
+    /// ```
+    #[inline]
+    pub fn split_weighted(self, weights: &[u64]) -> SplitWeighted<'_> {
+        let split = SplitWeighted::new(self.0, weights, None);
+        assert_ne!(split.total_weight, 0, "attempt to split an amount using zero weights");
+        split
+    }
+
+    /// Splits the amount into at most `parts` parts that differ by at most one millisatoshi and
+    /// are at least `min`.
+    ///
+    /// This works like [`split_even`](Amount::split_even) except the number of parts is reduced
+    /// if needed. This is useful to avoid creating HTLCs below the minimum accepted by the
+    /// channels. If the amount is zero the iterator yields no parts.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the amount is non-zero but lower than `min`.
+    ///
+    /// ## Panics
+    ///
+    /// This method panics if `parts` is zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// let amount = Amount::from_msat(1_000).unwrap();
+    /// let min = Amount::from_msat(300).unwrap();
+    /// let parts = amount.split_even_with_min(5, min).unwrap().map(Amount::to_msat).collect::<Vec<_>>();
+    /// assert_eq!(parts, [334, 333, 333]);
+    /// ```
+    pub fn split_even_with_min(self, parts: u64, min: Amount) -> Result<SplitEven, SplitError> {
+        let mut split = self.split_even(parts);
+        if self.0 != 0 && self.0 < min.0 {
+            return Err(SplitError { amount: self, min, });
+        }
+        if let Some(max_parts) = self.0.checked_div(min.0) {
+            split.parts = split.parts.min(max_parts);
+        }
+        Ok(split)
+    }
+
+    /// Splits the amount into parts proportional to `weights` such that each non-zero part is at
+    /// least `min`.
+    ///
+    /// This works like [`split_weighted`](Amount::split_weighted) except the smallest weights are
+    /// excluded if needed. This is useful to avoid creating HTLCs below the minimum accepted by
+    /// the channels. Weights are excluded from the smallest one, equal weights are excluded from
+    /// the last one. The parts for excluded weights are zero so the items still correspond to the
+    /// weights.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the amount is non-zero but lower than `min`.
+    ///
+    /// ## Panics
+    ///
+    /// This method panics if all weights are zero (including the case when there are none).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// let amount = Amount::from_msat(1_000).unwrap();
+    /// let min = Amount::from_msat(300).unwrap();
+    /// let parts = amount.split_weighted_with_min(&[1, 4, 5], min).unwrap().map(Amount::to_msat).collect::<Vec<_>>();
+    /// assert_eq!(parts, [0, 444, 556]);
+    /// ```
+    pub fn split_weighted_with_min(self, weights: &[u64], min: Amount) -> Result<SplitWeighted<'_>, SplitError> {
+        let split = self.split_weighted(weights);
+        if self.0 != 0 && self.0 < min.0 {
+            return Err(SplitError { amount: self, min, });
+        }
+
+        let satisfies_min = |split: &SplitWeighted<'_>| split.clone().all(|part| part == Amount::ZERO || part >= min);
+        if satisfies_min(&split) {
+            return Ok(split);
+        }
+
+        // Sets of active weights for different thresholds are nested so we pick the largest one
+        // that works. The set containing only the largest weight always works because the whole
+        // amount goes to it.
+        let best = weights
+            .iter()
+            .enumerate()
+            .filter(|&(_, &weight)| weight != 0)
+            .map(|(index, &weight)| SplitWeighted::new(self.0, weights, Some((weight, index))))
+            .filter(satisfies_min)
+            .max_by_key(|split| weights.iter().enumerate().filter(|&(index, &weight)| SplitWeighted::is_active(split.threshold, index, weight)).count())
+            .expect("the largest weight alone always satisfies the minimum");
+        Ok(best)
+    }
+
+    /// Encodes the amount as used in the human-readable part of BOLT 11 invoices.
+    ///
+    /// The amount is encoded as an integer followed by a multiplier (`m`, `u`, `n` or `p`)
+    /// which is omitted if the amount is a whole number of bitcoins.
+    /// The shortest encoding is always chosen, as required by BOLT 11.
+    ///
+    /// Note that invoices with zero amount should omit the amount instead but this method
+    /// returns `0` in such case so that it's reversible.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::from_sat(250_000).unwrap().to_bolt11_hrp_string(), "2500u");
+    /// assert_eq!(Amount::from_msat(1_000).unwrap().to_bolt11_hrp_string(), "10n");
+    /// assert_eq!(Amount::from_msat(1).unwrap().to_bolt11_hrp_string(), "10p");
+    /// assert_eq!(Amount::ONE_BTC.to_bolt11_hrp_string(), "1");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_bolt11_hrp_string(self) -> String {
+        use alloc::string::ToString;
+
+        let multiplier = Bolt11Multiplier::ALL
+            .iter()
+            .copied()
+            .find(|multiplier| self.0 % multiplier.msat_divisor() == 0)
+            .unwrap_or(Bolt11Multiplier::Pico);
+
+        let mut result = match multiplier {
+            // Can overflow u64 for large amounts
+            Bolt11Multiplier::Pico => (u128::from(self.0) * 10).to_string(),
+            _ => (self.0 / multiplier.msat_divisor()).to_string(),
+        };
+        if let Some(symbol) = multiplier.symbol() {
+            result.push(symbol);
+        }
+        result
+    }
+
+    /// Decodes the amount as used in the human-readable part of BOLT 11 invoices.
+    ///
+    /// The input must be a decimal integer without leading zeros optionally followed by a
+    /// multiplier (`m`, `u`, `n` or `p`, case-insensitive).
+    /// Pico-bitcoin amounts must be multiples of 10 because they can not be represented in
+    /// millisatoshis otherwise.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the input is malformed, the amount is not a whole number
+    /// of millisatoshis or it exceeds the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::Amount;
+    ///
+    /// assert_eq!(Amount::from_bolt11_hrp_str("2500u").unwrap(), Amount::from_sat(250_000).unwrap());
+    /// assert_eq!(Amount::from_bolt11_hrp_str("20m").unwrap(), Amount::from_sat(2_000_000).unwrap());
+    /// assert_eq!(Amount::from_bolt11_hrp_str("10p").unwrap(), Amount::ONE_MSAT);
+    /// assert!(Amount::from_bolt11_hrp_str("1p").is_err());
+    /// ```
+    pub fn from_bolt11_hrp_str(s: &str) -> Result<Self, Bolt11ParseError> {
+        Self::parse_bolt11_hrp_raw(s).map_err(|error| Bolt11ParseError {
+            #[cfg(feature = "alloc")]
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    fn parse_bolt11_hrp_raw(s: &str) -> Result<Self, Bolt11ParseErrorInner> {
+        let (number, multiplier) = match s.chars().last() {
+            None => return Err(Bolt11ParseErrorInner::Empty),
+            Some(c) if c.is_ascii_digit() => (s, Bolt11Multiplier::Btc),
+            Some(c) => {
+                let multiplier = Bolt11Multiplier::from_symbol(c).ok_or(Bolt11ParseErrorInner::UnknownMultiplier(c))?;
+                (&s[..(s.len() - c.len_utf8())], multiplier)
+            },
+        };
+
+        if let Some((pos, c)) = number.char_indices().find(|&(_, c)| !c.is_ascii_digit()) {
+            return Err(Bolt11ParseErrorInner::InvalidChar { pos, c, });
+        }
+        if number.is_empty() {
+            return Err(Bolt11ParseErrorInner::MissingNumber);
+        }
+        if number.len() > 1 && number.starts_with('0') {
+            return Err(Bolt11ParseErrorInner::LeadingZero);
+        }
+        // Pico-bitcoin amounts may not fit into u64
+        let number = number.parse::<u128>().map_err(|_| Bolt11ParseErrorInner::TooLarge)?;
+
+        let msat = match multiplier {
+            Bolt11Multiplier::Pico if number % 10 != 0 => return Err(Bolt11ParseErrorInner::SubMillisatoshi(number)),
+            Bolt11Multiplier::Pico => Some(number / 10),
+            _ => number.checked_mul(multiplier.msat_divisor().into()),
+        };
+
+        match msat.and_then(|msat| u64::try_from(msat).ok()).map(Self::from_msat) {
+            Some(Ok(amount)) => Ok(amount),
+            _ => {
+                let amount = u64::try_from(number).map_err(|_| Bolt11ParseErrorInner::TooLarge)?;
+                Err(Bolt11ParseErrorInner::Overflow(OverflowError { amount, denomination: multiplier.name(), negative: false, }))
+            },
+        }
+    }
+
+    /// Returns an object that displays the amount in given denomination.
+    ///
+    /// The returned value implements [`fmt::Display`] which shows the amount followed by the
+    /// denomination symbol - e.g. `0.001 BTC`. The value is shown exactly - trailing zeros are
+    /// removed but no information is lost. If precision is specified (e.g. `{:.2}`) the amount
+    /// is shown with exactly that many decimal places, rounding half up if needed.
+    /// Width, fill, alignment and zero padding are honored as well.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, Denomination};
+    ///
+    /// let amount = Amount::from_msat(1_500).unwrap();
+    /// assert_eq!(amount.display_in(Denomination::Sat).to_string(), "1.5 sat");
+    /// assert_eq!(format!("{:.2}", amount.display_in(Denomination::Sat)), "1.50 sat");
+    /// assert_eq!(format!("{:>12}", amount.display_in(Denomination::Sat)), "     1.5 sat");
+    /// assert_eq!(Amount::ONE_BTC.display_in(Denomination::Btc).to_string(), "1 BTC");
+    /// ```
+    #[inline]
+    pub fn display_in(self, denomination: Denomination) -> DisplayIn {
+        DisplayIn {
+            amount: self,
+            denomination,
+            show_denomination: true,
+            negative: false,
+            explicit_sign: false,
+        }
+    }
+
+    /// Parses a decimal number in given denomination.
+    ///
+    /// The number may optionally be followed by the symbol of the denomination using the same
+    /// rules as [`FromStr`] so the output of [`display_in`](Amount::display_in) can be parsed back.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the input is not a valid decimal number, if it has more
+    /// decimal places than can be represented in millisatoshis, if it exceeds the supply cap or
+    /// if the symbol doesn't match `denomination`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, Denomination};
+    ///
+    /// let amount = Amount::from_str_in("0.001 BTC", Denomination::Btc).unwrap();
+    /// assert_eq!(amount, Amount::from_sat(100_000).unwrap());
+    /// let amount = Amount::from_str_in("1.234", Denomination::Sat).unwrap();
+    /// assert_eq!(amount.to_msat(), 1234);
+    /// assert!(Amount::from_str_in("1.2345", Denomination::Sat).is_err());
+    /// ```
+    #[inline]
+    pub fn from_str_in(s: &str, denomination: Denomination) -> Result<Self, ParseError> {
+        Self::internal_parse_in(s, denomination)
+    }
+
+    /// Internal monomorphic parsing of decimal number in given denomination.
+    fn parse_in_raw(s: &str, denomination: Denomination) -> Result<Self, ParseErrorInner> {
+        match Self::split_denomination(s)? {
+            (number, None) => Self::parse_decimal(number, denomination),
+            (number, Some(symbol)) if symbol == denomination => Self::parse_decimal(number, denomination),
+            (_, Some(_)) => Err(ParseErrorInner::DenominationMismatch { expected: denomination, }),
+        }
+    }
+
+    /// Splits the input into the number and the denomination following it, if any.
+    ///
+    /// The denomination may be separated from the number by whitespace.
+    fn split_denomination(s: &str) -> Result<(&str, Option<Denomination>), ParseErrorInner> {
+        let number_end = s
+            .find(|c: char| c.is_alphabetic() || c.is_whitespace())
+            .unwrap_or(s.len());
+        let (number, suffix) = s.split_at(number_end);
+        let suffix = suffix.trim_start();
+
+        if suffix.is_empty() {
+            return Ok((number, None));
+        }
+        match Denomination::from_symbol(suffix) {
+            Some(denomination) => Ok((number, Some(denomination))),
+            // Produces better error message for inputs like `foo`
+            None if number.is_empty() => Ok((s, None)),
+            None => Err(ParseErrorInner::UnknownDenomination),
+        }
+    }
+
+    /// Parses decimal number without any suffix.
+    fn parse_decimal(s: &str, denomination: Denomination) -> Result<Self, ParseErrorInner> {
+        // `u64::from_str` accepts a leading `+` which is not desired here.
+        if s.starts_with(|c| c == '+' || c == '-') {
+            return Err(ParseErrorInner::UnexpectedSign);
+        }
+        let decimal_places = denomination.decimal_places();
+        let (integer, fraction) = match s.find('.') {
+            Some(pos) => (&s[..pos], Some((pos + 1, &s[(pos + 1)..]))),
+            None => (s, None),
+        };
+        let integer = integer.parse::<u64>()?;
+        let mut fraction_msat = 0;
+        if let Some((offset, fraction)) = fraction {
+            if fraction.is_empty() {
+                return Err(ParseErrorInner::EmptyFraction);
+            }
+
+            for (i, c) in fraction.char_indices() {
+                let digit = c.to_digit(10).ok_or(ParseErrorInner::FractionChar { pos: offset + i, c, })?;
+                if i < decimal_places {
+                    fraction_msat = fraction_msat * 10 + u64::from(digit);
+                } else if digit != 0 {
+                    return Err(ParseErrorInner::TooPrecise);
+                }
+            }
+            if fraction.len() < decimal_places {
+                fraction_msat *= pow10(decimal_places - fraction.len());
+            }
         }
 
-        let amount = s.parse::<u64>()?;
+        let msat = integer
+            .checked_mul(denomination.msat_per_unit())
+            .and_then(|msat| msat.checked_add(fraction_msat))
+            .ok_or(OverflowError { amount: integer, denomination: denomination.name(), negative: false, })?;
+
+        Self::from_msat(msat).map_err(Into::into)
+    }
+
+    /// Generic wrapper for parsing in denomination.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse_in<S: AsRef<str> + Into<String>>(s: S, denomination: Denomination) -> Result<Self, ParseError> {
+        Self::parse_in_raw(s.as_ref(), denomination).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    /// Generic wrapper for parsing in denomination.
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse_in<S: AsRef<str>>(s: S, denomination: Denomination) -> Result<Self, ParseError> {
+        Self::parse_in_raw(s.as_ref(), denomination).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
 
-        Self::from_msat(amount).map_err(Into::into)
+    /// Internal monomorphic parsing method.
+    ///
+    /// This should improve codegen without requiring allocations.
+    fn parse_raw(s: &str) -> Result<Self, ParseErrorInner> {
+        let (number, denomination) = Self::split_denomination(s)?;
+        Self::parse_decimal(number, denomination.unwrap_or(Denomination::Msat))
     }
 
     /// Generic wrapper for parsing that is used to implement parsing from multiple types.
@@ -201,6 +725,8 @@ impl Amount {
 }
 
 /// Displays the amount followed by denomination ` msat`.
+///
+/// Formatting options are ignored, use [`display_in`](Amount::display_in) to control them.
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} msat", self.0)
@@ -214,46 +740,346 @@ impl fmt::Debug for Amount {
     }
 }
 
+/// Computes `10^exp`
+fn pow10(exp: usize) -> u64 {
+    10u64.pow(exp as u32)
+}
 
-/// Panics on overflow
-impl core::ops::Add for Amount {
-    type Output = Self;
-
-    #[inline]
-    fn add(self, rhs: Amount) -> Self::Output {
-        let sum = self.0 + rhs.0;
-        assert!(
-            sum <= MAX_MONEY_MSAT, 
-            "adding amounts {} + {} overflowed the maximum number of 21 million bitcoins",
-            self,
-            rhs,
-        );
+/// Computes `a * b / c` rounded down without overflowing.
+///
+/// `b` must not be greater than `c`.
+fn mul_div(a: u64, b: u128, c: u128) -> u128 {
+    if let Some(product) = u128::from(a).checked_mul(b) {
+        return product / c;
+    }
 
-        Amount(sum)
+    // Binary long multiplication keeping the intermediate values below `3 * c`. This can't
+    // overflow because a sum of `u64` weights in a slice is far below `u128::MAX / 3`.
+    let mut quotient = 0;
+    let mut remainder = 0;
+    for bit in (0..64).rev() {
+        remainder *= 2;
+        if a & (1 << bit) != 0 {
+            remainder += b;
+        }
+        quotient = quotient * 2 + remainder / c;
+        remainder %= c;
     }
+    quotient
 }
 
-/// Panics on overflow
-impl core::ops::AddAssign for Amount {
-    #[inline]
-    fn add_assign(&mut self, rhs: Amount) {
-        *self = *self + rhs;
+/// Counts decimal digits required to display the number.
+fn decimal_digits(mut number: u64) -> usize {
+    let mut digits = 1;
+    while number >= 10 {
+        number /= 10;
+        digits += 1;
     }
+    digits
 }
 
-/// Panics on overflow
-impl core::ops::Sub for Amount {
-    type Output = Self;
-
-    #[inline]
-    fn sub(self, rhs: Amount) -> Self::Output {
-        Amount(self.0.checked_sub(rhs.0).expect("underflow when subtracting amounts"))
-    }
+/// Unit in which an amount is displayed or parsed.
+///
+/// This is used by [`Amount::display_in`] and [`Amount::from_str_in`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Denomination {
+    /// Bitcoin, the symbol is `BTC`
+    Btc,
+    /// Satoshi, the symbol is `sat`
+    Sat,
+    /// Millisatoshi, the symbol is `msat`
+    Msat,
 }
 
-/// Panics on overflow
-impl core::ops::SubAssign for Amount {
-    #[inline]
+impl Denomination {
+    /// Returns the symbol of the denomination.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// assert_eq!(ln_types::amount::Denomination::Btc.as_str(), "BTC");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Denomination::Btc => "BTC",
+            Denomination::Sat => "sat",
+            Denomination::Msat => "msat",
+        }
+    }
+
+    /// Recognizes the symbol of the denomination case-insensitively.
+    ///
+    /// Only `sats` and `msats` plurals are accepted, `btcs` is not.
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        let is = |expected: &str| symbol.eq_ignore_ascii_case(expected);
+        if is("btc") {
+            Some(Denomination::Btc)
+        } else if is("sat") || is("sats") {
+            Some(Denomination::Sat)
+        } else if is("msat") || is("msats") {
+            Some(Denomination::Msat)
+        } else {
+            None
+        }
+    }
+
+    /// Number of decimal places needed to represent a millisatoshi.
+    fn decimal_places(self) -> usize {
+        match self {
+            Denomination::Btc => 11,
+            Denomination::Sat => 3,
+            Denomination::Msat => 0,
+        }
+    }
+
+    fn msat_per_unit(self) -> u64 {
+        pow10(self.decimal_places())
+    }
+
+    /// Name used in error messages.
+    fn name(self) -> &'static str {
+        match self {
+            Denomination::Btc => "bitcoins",
+            Denomination::Sat => "satoshis",
+            Denomination::Msat => "millisatoshis",
+        }
+    }
+}
+
+/// Displays the symbol
+impl fmt::Display for Denomination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Multipliers used in BOLT 11 human-readable part, ordered from the largest.
+#[derive(Debug, Copy, Clone)]
+enum Bolt11Multiplier {
+    Btc,
+    Milli,
+    Micro,
+    Nano,
+    Pico,
+}
+
+impl Bolt11Multiplier {
+    #[cfg(feature = "alloc")]
+    const ALL: [Bolt11Multiplier; 5] = [
+        Bolt11Multiplier::Btc,
+        Bolt11Multiplier::Milli,
+        Bolt11Multiplier::Micro,
+        Bolt11Multiplier::Nano,
+        Bolt11Multiplier::Pico,
+    ];
+
+    fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            'm' | 'M' => Some(Bolt11Multiplier::Milli),
+            'u' | 'U' => Some(Bolt11Multiplier::Micro),
+            'n' | 'N' => Some(Bolt11Multiplier::Nano),
+            'p' | 'P' => Some(Bolt11Multiplier::Pico),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn symbol(self) -> Option<char> {
+        match self {
+            Bolt11Multiplier::Btc => None,
+            Bolt11Multiplier::Milli => Some('m'),
+            Bolt11Multiplier::Micro => Some('u'),
+            Bolt11Multiplier::Nano => Some('n'),
+            Bolt11Multiplier::Pico => Some('p'),
+        }
+    }
+
+    /// Number of millisatoshis in one unit.
+    ///
+    /// Pico-bitcoin is one tenth of millisatoshi so it must be handled separately.
+    fn msat_divisor(self) -> u64 {
+        match self {
+            Bolt11Multiplier::Btc => SATS_IN_BTC * 1000,
+            Bolt11Multiplier::Milli => SATS_IN_BTC,
+            Bolt11Multiplier::Micro => SATS_IN_BTC / 1000,
+            Bolt11Multiplier::Nano => SATS_IN_BTC / 1_000_000,
+            Bolt11Multiplier::Pico => 1,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Bolt11Multiplier::Btc => "bitcoins",
+            Bolt11Multiplier::Milli => "milli-bitcoins",
+            Bolt11Multiplier::Micro => "micro-bitcoins",
+            Bolt11Multiplier::Nano => "nano-bitcoins",
+            Bolt11Multiplier::Pico => "pico-bitcoins",
+        }
+    }
+}
+
+/// Displays an amount in given denomination.
+///
+/// This is returned from [`Amount::display_in`] and [`SignedAmount::display_in`], see their
+/// documentation for details.
+#[derive(Debug, Copy, Clone)]
+pub struct DisplayIn {
+    /// Absolute value
+    amount: Amount,
+    denomination: Denomination,
+    show_denomination: bool,
+    negative: bool,
+    /// Show `+` even if not requested by the formatter
+    explicit_sign: bool,
+}
+
+impl DisplayIn {
+    /// Displays only the number, without the denomination symbol.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, Denomination};
+    ///
+    /// let amount = Amount::from_sat(42).unwrap();
+    /// assert_eq!(amount.display_in(Denomination::Sat).without_denomination().to_string(), "42");
+    /// ```
+    #[inline]
+    pub fn without_denomination(mut self) -> Self {
+        self.show_denomination = false;
+        self
+    }
+}
+
+/// Honors precision, width, fill, alignment (right by default), zero padding and the `+` flag.
+impl fmt::Display for DisplayIn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+
+        let decimal_places = self.denomination.decimal_places();
+        let mut integer = self.amount.0 / self.denomination.msat_per_unit();
+        let mut fraction = self.amount.0 % self.denomination.msat_per_unit();
+        let mut fraction_digits = decimal_places;
+        let mut trailing_zeros = 0;
+
+        match f.precision() {
+            Some(precision) if precision < decimal_places => {
+                let divisor = pow10(decimal_places - precision);
+                let remainder = fraction % divisor;
+                fraction /= divisor;
+                if remainder * 2 >= divisor {
+                    fraction += 1;
+                    if fraction == pow10(precision) {
+                        fraction = 0;
+                        integer += 1;
+                    }
+                }
+                fraction_digits = precision;
+            },
+            Some(precision) => trailing_zeros = precision - decimal_places,
+            None => {
+                while fraction_digits > 0 && fraction % 10 == 0 {
+                    fraction /= 10;
+                    fraction_digits -= 1;
+                }
+            },
+        }
+
+        let sign = if self.negative {
+            Some('-')
+        } else if f.sign_plus() || (self.explicit_sign && self.amount != Amount::ZERO) {
+            Some('+')
+        } else {
+            None
+        };
+
+        let mut len = decimal_digits(integer) + usize::from(sign.is_some());
+        if fraction_digits + trailing_zeros > 0 {
+            len += 1 + fraction_digits + trailing_zeros;
+        }
+        if self.show_denomination {
+            len += 1 + self.denomination.as_str().len();
+        }
+        let padding = f.width().unwrap_or(0).saturating_sub(len);
+        let (fill, pre, post) = if f.sign_aware_zero_pad() {
+            ('0', padding, 0)
+        } else {
+            match f.align() {
+                Some(fmt::Alignment::Left) => (f.fill(), 0, padding),
+                Some(fmt::Alignment::Center) => (f.fill(), padding / 2, (padding + 1) / 2),
+                Some(fmt::Alignment::Right) | None => (f.fill(), padding, 0),
+            }
+        };
+
+        if let (Some(sign), true) = (sign, f.sign_aware_zero_pad()) {
+            f.write_char(sign)?;
+        }
+        for _ in 0..pre {
+            f.write_char(fill)?;
+        }
+        if let (Some(sign), false) = (sign, f.sign_aware_zero_pad()) {
+            f.write_char(sign)?;
+        }
+        write!(f, "{}", integer)?;
+        if fraction_digits + trailing_zeros > 0 {
+            f.write_char('.')?;
+            if fraction_digits > 0 {
+                write!(f, "{:0width$}", fraction, width = fraction_digits)?;
+            }
+            for _ in 0..trailing_zeros {
+                f.write_char('0')?;
+            }
+        }
+        if self.show_denomination {
+            write!(f, " {}", self.denomination.as_str())?;
+        }
+        for _ in 0..post {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Add for Amount {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Amount) -> Self::Output {
+        let sum = self.0 + rhs.0;
+        assert!(
+            sum <= MAX_MONEY_MSAT, 
+            "adding amounts {} + {} overflowed the maximum number of 21 million bitcoins",
+            self,
+            rhs,
+        );
+
+        Amount(sum)
+    }
+}
+
+/// Panics on overflow
+impl core::ops::AddAssign for Amount {
+    #[inline]
+    fn add_assign(&mut self, rhs: Amount) {
+        *self = *self + rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Sub for Amount {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Amount) -> Self::Output {
+        Amount(self.0.checked_sub(rhs.0).expect("underflow when subtracting amounts"))
+    }
+}
+
+/// Panics on overflow
+impl core::ops::SubAssign for Amount {
+    #[inline]
     fn sub_assign(&mut self, rhs: Amount) {
         *self = *self - rhs
     }
@@ -295,29 +1121,804 @@ impl core::ops::Div<u64> for Amount {
     }
 }
 
-impl core::ops::DivAssign<u64> for Amount {
-    fn div_assign(&mut self, rhs: u64) {
-        *self = *self / rhs;
+impl core::ops::DivAssign<u64> for Amount {
+    fn div_assign(&mut self, rhs: u64) {
+        *self = *self / rhs;
+    }
+}
+
+impl core::ops::Rem<u64> for Amount {
+    type Output = Self;
+
+    fn rem(self, rhs: u64) -> Self::Output {
+        Amount(self.0 % rhs)
+    }
+}
+
+impl core::ops::RemAssign<u64> for Amount {
+    fn rem_assign(&mut self, rhs: u64) {
+        *self = *self % rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::iter::Sum for Amount {
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Self {
+        iter.fold(Amount::ZERO, core::ops::Add::add)
+    }
+}
+
+/// Panics on overflow
+impl<'a> core::iter::Sum<&'a Amount> for Amount {
+    fn sum<I: Iterator<Item = &'a Amount>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Returns `None` on overflow
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::Amount;
+///
+/// let sum = [Amount::ONE_SAT, Amount::ONE_MSAT].iter().sum::<Option<Amount>>();
+/// assert_eq!(sum.unwrap().to_msat(), 1001);
+/// let sum = [Amount::MAX, Amount::ONE_MSAT].iter().sum::<Option<Amount>>();
+/// assert!(sum.is_none());
+/// ```
+impl core::iter::Sum<Amount> for Option<Amount> {
+    fn sum<I: Iterator<Item = Amount>>(mut iter: I) -> Self {
+        iter.try_fold(Amount::ZERO, Amount::checked_add)
+    }
+}
+
+/// Returns `None` on overflow
+impl<'a> core::iter::Sum<&'a Amount> for Option<Amount> {
+    fn sum<I: Iterator<Item = &'a Amount>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Iterator over parts of an evenly split amount.
+///
+/// This is returned from [`Amount::split_even`] and [`Amount::split_even_with_min`], see their
+/// documentation for details.
+#[derive(Debug, Clone)]
+pub struct SplitEven {
+    amount: u64,
+    parts: u64,
+    index: u64,
+}
+
+impl Iterator for SplitEven {
+    type Item = Amount;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.parts {
+            return None;
+        }
+        let mut part = self.amount / self.parts;
+        if self.index < self.amount % self.parts {
+            part += 1;
+        }
+        self.index += 1;
+        Some(Amount(part))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.parts - self.index;
+        match usize::try_from(remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl core::iter::FusedIterator for SplitEven {}
+
+/// Iterator over parts of an amount split according to weights.
+///
+/// This is returned from [`Amount::split_weighted`] and [`Amount::split_weighted_with_min`], see
+/// their documentation for details.
+#[derive(Debug, Clone)]
+pub struct SplitWeighted<'a> {
+    amount: u64,
+    weights: core::slice::Iter<'a, u64>,
+    /// Weights smaller than this (ordered by weight and reverse index) are excluded.
+    threshold: Option<(u64, usize)>,
+    total_weight: u128,
+    index: usize,
+    cumulative_weight: u128,
+    emitted: u64,
+}
+
+impl<'a> SplitWeighted<'a> {
+    fn new(amount: u64, weights: &'a [u64], threshold: Option<(u64, usize)>) -> Self {
+        let total_weight = weights
+            .iter()
+            .enumerate()
+            .filter(|&(index, &weight)| Self::is_active(threshold, index, weight))
+            .map(|(_, &weight)| u128::from(weight))
+            .sum();
+
+        SplitWeighted {
+            amount,
+            weights: weights.iter(),
+            threshold,
+            total_weight,
+            index: 0,
+            cumulative_weight: 0,
+            emitted: 0,
+        }
+    }
+
+    fn is_active(threshold: Option<(u64, usize)>, index: usize, weight: u64) -> bool {
+        match threshold {
+            _ if weight == 0 => false,
+            None => true,
+            Some((min_weight, max_index)) => weight > min_weight || (weight == min_weight && index <= max_index),
+        }
+    }
+}
+
+impl<'a> Iterator for SplitWeighted<'a> {
+    type Item = Amount;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let weight = *self.weights.next()?;
+        if Self::is_active(self.threshold, self.index, weight) {
+            self.cumulative_weight += u128::from(weight);
+        }
+        self.index += 1;
+        // Computing the cumulative amount avoids accumulating rounding errors.
+        // It's at most `amount` so it fits `u64`.
+        let end = mul_div(self.amount, self.cumulative_weight, self.total_weight) as u64;
+        let part = end - self.emitted;
+        self.emitted = end;
+        Some(Amount(part))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.weights.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for SplitWeighted<'a> {}
+
+impl<'a> core::iter::FusedIterator for SplitWeighted<'a> {}
+
+/// Accepts a non-negative decimal number up to 21 000 000 BTC
+///
+/// The number may be followed by a case-insensitive denomination `btc`, `sat(s)` or `msat(s)`
+/// (optionally separated by whitespace), e.g. `0.05btc`, `2500 sats` or `1.5 sat`.
+/// The number is interpreted as millisatoshis if the denomination is missing.
+/// Values with sub-millisatoshi precision are rejected.
+impl FromStr for Amount {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts a non-negative decimal number up to 21 000 000 BTC
+///
+/// The number may be followed by a case-insensitive denomination `btc`, `sat(s)` or `msat(s)`
+/// (optionally separated by whitespace), e.g. `0.05btc`, `2500 sats` or `1.5 sat`.
+/// The number is interpreted as millisatoshis if the denomination is missing.
+/// Values with sub-millisatoshi precision are rejected.
+impl<'a> TryFrom<&'a str> for Amount {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts a non-negative decimal number up to 21 000 000 BTC
+///
+/// The number may be followed by a case-insensitive denomination `btc`, `sat(s)` or `msat(s)`
+/// (optionally separated by whitespace), e.g. `0.05btc`, `2500 sats` or `1.5 sat`.
+/// The number is interpreted as millisatoshis if the denomination is missing.
+/// Values with sub-millisatoshi precision are rejected.
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for Amount {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts a non-negative decimal number up to 21 000 000 BTC
+///
+/// The number may be followed by a case-insensitive denomination `btc`, `sat(s)` or `msat(s)`
+/// (optionally separated by whitespace), e.g. `0.05btc`, `2500 sats` or `1.5 sat`.
+/// The number is interpreted as millisatoshis if the denomination is missing.
+/// Values with sub-millisatoshi precision are rejected.
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for Amount {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Signed number of millisatoshis.
+///
+/// This type represents a change of balance or a result of fee accounting which may be negative.
+/// It provides ordinary arithmetic both with other signed amounts and with [`Amount`], as well as
+/// conversion methods.
+///
+/// ## Invariants
+///
+/// This type guarantees that the absolute value of the amount stays less than or equal to 21
+/// million bitcoins. However `unsafe` code **must not** rely on this, at least for now.
+/// This implies that arithmetic operations always panic on overflow.
+/// Use the `checked_*` or `saturating_*` methods if the values are untrusted.
+///
+/// ## `Display` implementation
+///
+/// The amount is displayed with an explicit sign (unless it's zero) and ` msat` suffix - e.g.
+/// `-42 msat` or `+42 msat`.
+/// Use [`display_in`](SignedAmount::display_in) to display the amount in other denominations.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct SignedAmount(i64);
+
+impl SignedAmount {
+    /// Zero bitcoins.
+    pub const ZERO: SignedAmount = SignedAmount(0);
+
+    /// One millisatoshi
+    pub const ONE_MSAT: SignedAmount = SignedAmount(1);
+
+    /// One satoshi
+    pub const ONE_SAT: SignedAmount = SignedAmount(1000);
+
+    /// One bitcoin
+    pub const ONE_BTC: SignedAmount = SignedAmount(1000 * SATS_IN_BTC as i64);
+
+    /// 21 million bitcoins.
+    pub const MAX: SignedAmount = SignedAmount(MAX_MONEY_MSAT as i64);
+
+    /// Minus 21 million bitcoins.
+    pub const MIN: SignedAmount = SignedAmount(-(MAX_MONEY_MSAT as i64));
+
+    /// Constructs the amount from raw millisatosis.
+    ///
+    /// The value is directly converted with an overflow check.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the absolute value exceeds Bitcoin supply cap
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let msat = ln_types::amount::SignedAmount::from_msat(-1000).unwrap();
+    /// assert_eq!(msat, -ln_types::amount::SignedAmount::ONE_SAT);
+    /// ```
+    #[inline]
+    pub fn from_msat(msat: i64) -> Result<Self, OverflowError> {
+        if msat.unsigned_abs() > MAX_MONEY_MSAT {
+            Err(OverflowError { amount: msat.unsigned_abs(), denomination: "millisatoshis", negative: msat < 0, })
+        } else {
+            Ok(SignedAmount(msat))
+        }
+    }
+
+    /// Constructs the amount from raw satosis.
+    ///
+    /// The value is converted with an overflow check.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the absolute value exceeds Bitcoin supply cap
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let msat = ln_types::amount::SignedAmount::from_sat(-100_000_000).unwrap();
+    /// assert_eq!(msat, -ln_types::amount::SignedAmount::ONE_BTC);
+    /// ```
+    #[inline]
+    pub fn from_sat(sat: i64) -> Result<Self, OverflowError> {
+        if sat.unsigned_abs() > MAX_MONEY_SAT {
+            Err(OverflowError { amount: sat.unsigned_abs(), denomination: "satoshis", negative: sat < 0, })
+        } else {
+            Ok(SignedAmount(sat * 1000))
+        }
+    }
+
+    /// Converts the value to raw millisatoshis.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let msat = (-ln_types::amount::SignedAmount::ONE_SAT).to_msat();
+    /// assert_eq!(msat, -1000);
+    /// ```
+    #[inline]
+    pub fn to_msat(self) -> i64 {
+        self.0
+    }
+
+    /// Attempts to convert the value to raw satoshis.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the number of millisatoshis isn't rounded to thousands.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let msat = (-ln_types::amount::SignedAmount::ONE_SAT).to_sat().unwrap();
+    /// assert_eq!(msat, -1);
+    /// ```
+    #[inline]
+    pub fn to_sat(self) -> Result<i64, FractionError> {
+        if self.0 % 1000 == 0 {
+            Ok(self.0 / 1000)
+        } else {
+            Err(FractionError { amount: self.0.unsigned_abs(), negative: self.0 < 0, })
+        }
+    }
+
+    /// Returns the absolute value as [`Amount`].
+    ///
+    /// This can never fail because the absolute value is always within the bounds of `Amount`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, SignedAmount};
+    ///
+    /// assert_eq!((-SignedAmount::ONE_SAT).unsigned_abs(), Amount::ONE_SAT);
+    /// ```
+    #[inline]
+    pub fn unsigned_abs(self) -> Amount {
+        Amount(self.0.unsigned_abs())
+    }
+
+    /// Returns the absolute value.
+    #[inline]
+    pub fn abs(self) -> SignedAmount {
+        SignedAmount(self.0.abs())
+    }
+
+    /// Returns `true` if the amount is less than zero.
+    #[inline]
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns `true` if the amount is greater than zero.
+    #[inline]
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// Attempts to convert the value to [`Amount`].
+    ///
+    /// This is the same as `TryFrom` but more readable and less prone to inference problems.
+    ///
+    /// ## Errors
+    ///
+    /// This method returns an error if the amount is negative.
+    #[inline]
+    pub fn to_amount(self) -> Result<Amount, NegativeError> {
+        if self.0 < 0 {
+            Err(NegativeError { amount: self, })
+        } else {
+            Ok(Amount(self.0 as u64))
+        }
+    }
+
+    /// Adds two amounts, returning `None` if the result exceeds the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::SignedAmount;
+    ///
+    /// assert_eq!(SignedAmount::MIN.checked_add(SignedAmount::MAX).unwrap(), SignedAmount::ZERO);
+    /// assert!(SignedAmount::MIN.checked_add(-SignedAmount::ONE_MSAT).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_add(self, rhs: SignedAmount) -> Option<SignedAmount> {
+        // Can't overflow i64 because both are at most MAX_MONEY_MSAT in absolute value
+        let sum = self.0 + rhs.0;
+        if sum.unsigned_abs() <= MAX_MONEY_MSAT {
+            Some(SignedAmount(sum))
+        } else {
+            None
+        }
+    }
+
+    /// Subtracts `rhs` from `self`, returning `None` if the result exceeds the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::SignedAmount;
+    ///
+    /// assert_eq!(SignedAmount::ZERO.checked_sub(SignedAmount::ONE_SAT).unwrap(), -SignedAmount::ONE_SAT);
+    /// assert!(SignedAmount::MIN.checked_sub(SignedAmount::ONE_MSAT).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_sub(self, rhs: SignedAmount) -> Option<SignedAmount> {
+        self.checked_add(SignedAmount(-rhs.0))
+    }
+
+    /// Multiplies the amount by an integer, returning `None` if the result exceeds the supply cap.
+    #[inline]
+    pub const fn checked_mul(self, rhs: i64) -> Option<SignedAmount> {
+        match self.0.checked_mul(rhs) {
+            Some(product) if product.unsigned_abs() <= MAX_MONEY_MSAT => Some(SignedAmount(product)),
+            _ => None,
+        }
+    }
+
+    /// Adds two amounts, returning [`SignedAmount::MAX`] or [`SignedAmount::MIN`] if the result
+    /// exceeds the supply cap.
+    #[inline]
+    pub const fn saturating_add(self, rhs: SignedAmount) -> SignedAmount {
+        match self.checked_add(rhs) {
+            Some(sum) => sum,
+            None if rhs.0 < 0 => SignedAmount::MIN,
+            None => SignedAmount::MAX,
+        }
+    }
+
+    /// Subtracts `rhs` from `self`, returning [`SignedAmount::MAX`] or [`SignedAmount::MIN`] if
+    /// the result exceeds the supply cap.
+    #[inline]
+    pub const fn saturating_sub(self, rhs: SignedAmount) -> SignedAmount {
+        self.saturating_add(SignedAmount(-rhs.0))
+    }
+
+    /// Returns an object that displays the amount in given denomination.
+    ///
+    /// This is the same as [`Amount::display_in`] except the sign is always shown if the amount
+    /// is not zero.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{SignedAmount, Denomination};
+    ///
+    /// let amount = SignedAmount::from_msat(-1_500).unwrap();
+    /// assert_eq!(amount.display_in(Denomination::Sat).to_string(), "-1.5 sat");
+    /// assert_eq!((-amount).display_in(Denomination::Sat).to_string(), "+1.5 sat");
+    /// assert_eq!(format!("{:08.2}", amount.display_in(Denomination::Sat).without_denomination()), "-0001.50");
+    /// ```
+    #[inline]
+    pub fn display_in(self, denomination: Denomination) -> DisplayIn {
+        DisplayIn {
+            amount: self.unsigned_abs(),
+            denomination,
+            show_denomination: true,
+            negative: self.is_negative(),
+            explicit_sign: true,
+        }
+    }
+
+    /// Parses a signed decimal number in given denomination.
+    ///
+    /// This is the same as [`Amount::from_str_in`] except the number may be preceded by `+` or
+    /// `-` sign.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{SignedAmount, Denomination};
+    ///
+    /// let amount = SignedAmount::from_str_in("-0.001 BTC", Denomination::Btc).unwrap();
+    /// assert_eq!(amount, SignedAmount::from_sat(-100_000).unwrap());
+    /// ```
+    #[inline]
+    pub fn from_str_in(s: &str, denomination: Denomination) -> Result<Self, ParseError> {
+        Self::internal_parse_with(s, |s| Self::parse_signed(s, |s| Amount::parse_in_raw(s, denomination)))
+    }
+
+    /// Strips the sign and parses the rest using `parse_unsigned`.
+    fn parse_signed<F: FnOnce(&str) -> Result<Amount, ParseErrorInner>>(s: &str, parse_unsigned: F) -> Result<Self, ParseErrorInner> {
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        // Avoid accepting `+-42` or `-+42`
+        if unsigned.starts_with(|c| c == '+' || c == '-') {
+            return Err(ParseErrorInner::DoubleSign);
+        }
+
+        match parse_unsigned(unsigned) {
+            Ok(amount) if negative => Ok(-SignedAmount::from(amount)),
+            Ok(amount) => Ok(SignedAmount::from(amount)),
+            Err(ParseErrorInner::Overflow(mut error)) => {
+                error.negative = negative;
+                Err(ParseErrorInner::Overflow(error))
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse_with<S: AsRef<str> + Into<String>, F: FnOnce(&str) -> Result<Self, ParseErrorInner>>(s: S, parse: F) -> Result<Self, ParseError> {
+        parse(s.as_ref()).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse_with<S: AsRef<str>, F: FnOnce(&str) -> Result<Self, ParseErrorInner>>(s: S, parse: F) -> Result<Self, ParseError> {
+        parse(s.as_ref()).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+        Self::internal_parse_with(s, |s| Self::parse_signed(s, Amount::parse_raw))
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+        Self::internal_parse_with(s, |s| Self::parse_signed(s, Amount::parse_raw))
+    }
+}
+
+/// Displays the amount with explicit sign followed by denomination ` msat`.
+///
+/// Formatting options are honored the same way as in [`DisplayIn`].
+impl fmt::Display for SignedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.display_in(Denomination::Msat), f)
+    }
+}
+
+/// Displays the amount with explicit sign followed by denomination ` msat`.
+impl fmt::Debug for SignedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl From<Amount> for SignedAmount {
+    #[inline]
+    fn from(value: Amount) -> Self {
+        // Amount is guaranteed to be in bounds
+        SignedAmount(value.0 as i64)
+    }
+}
+
+impl TryFrom<SignedAmount> for Amount {
+    type Error = NegativeError;
+
+    #[inline]
+    fn try_from(value: SignedAmount) -> Result<Self, Self::Error> {
+        value.to_amount()
+    }
+}
+
+impl Amount {
+    /// Subtracts `rhs` from `self` producing a signed result.
+    ///
+    /// This never fails because the result is always within the bounds of [`SignedAmount`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, SignedAmount};
+    ///
+    /// assert_eq!(Amount::ZERO.signed_sub(Amount::ONE_SAT), -SignedAmount::ONE_SAT);
+    /// ```
+    #[inline]
+    pub fn signed_sub(self, rhs: Amount) -> SignedAmount {
+        SignedAmount(self.0 as i64 - rhs.0 as i64)
+    }
+
+    /// Adds a signed amount to `self`, returning `None` if the result is negative or exceeds the
+    /// supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::amount::{Amount, SignedAmount};
+    ///
+    /// assert_eq!(Amount::ONE_SAT.checked_add_signed(-SignedAmount::ONE_SAT).unwrap(), Amount::ZERO);
+    /// assert!(Amount::ZERO.checked_add_signed(-SignedAmount::ONE_MSAT).is_none());
+    /// ```
+    #[inline]
+    pub const fn checked_add_signed(self, rhs: SignedAmount) -> Option<Amount> {
+        let sum = self.0 as i64 + rhs.0;
+        if sum >= 0 && sum as u64 <= MAX_MONEY_MSAT {
+            Some(Amount(sum as u64))
+        } else {
+            None
+        }
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Add for SignedAmount {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: SignedAmount) -> Self::Output {
+        self.checked_add(rhs).unwrap_or_else(|| panic!("adding amounts {} + {} overflowed the limit of 21 million bitcoins", self, rhs))
+    }
+}
+
+/// Panics on overflow
+impl core::ops::AddAssign for SignedAmount {
+    #[inline]
+    fn add_assign(&mut self, rhs: SignedAmount) {
+        *self = *self + rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Sub for SignedAmount {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: SignedAmount) -> Self::Output {
+        self.checked_sub(rhs).unwrap_or_else(|| panic!("subtracting amounts {} - {} overflowed the limit of 21 million bitcoins", self, rhs))
+    }
+}
+
+/// Panics on overflow
+impl core::ops::SubAssign for SignedAmount {
+    #[inline]
+    fn sub_assign(&mut self, rhs: SignedAmount) {
+        *self = *self - rhs;
+    }
+}
+
+impl core::ops::Neg for SignedAmount {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        // The range is symmetric so this can not overflow
+        SignedAmount(-self.0)
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Mul<i64> for SignedAmount {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        self.checked_mul(rhs).unwrap_or_else(|| panic!("multiplying {} by {} overflowed the limit of 21 million bitcoins", self, rhs))
+    }
+}
+
+/// Panics on overflow
+impl core::ops::MulAssign<i64> for SignedAmount {
+    fn mul_assign(&mut self, rhs: i64) {
+        *self = *self * rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Add<Amount> for SignedAmount {
+    type Output = SignedAmount;
+
+    #[inline]
+    fn add(self, rhs: Amount) -> Self::Output {
+        self + SignedAmount::from(rhs)
+    }
+}
+
+/// Panics on overflow
+impl core::ops::AddAssign<Amount> for SignedAmount {
+    #[inline]
+    fn add_assign(&mut self, rhs: Amount) {
+        *self = *self + rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::ops::Sub<Amount> for SignedAmount {
+    type Output = SignedAmount;
+
+    #[inline]
+    fn sub(self, rhs: Amount) -> Self::Output {
+        self - SignedAmount::from(rhs)
+    }
+}
+
+/// Panics on overflow
+impl core::ops::SubAssign<Amount> for SignedAmount {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Amount) {
+        *self = *self - rhs;
+    }
+}
+
+/// Panics if the result is negative or overflows
+impl core::ops::Add<SignedAmount> for Amount {
+    type Output = Amount;
+
+    #[inline]
+    fn add(self, rhs: SignedAmount) -> Self::Output {
+        self.checked_add_signed(rhs).unwrap_or_else(|| panic!("adding amounts {} + {} is out of range", self, rhs))
+    }
+}
+
+/// Panics if the result is negative or overflows
+impl core::ops::AddAssign<SignedAmount> for Amount {
+    #[inline]
+    fn add_assign(&mut self, rhs: SignedAmount) {
+        *self = *self + rhs;
+    }
+}
+
+/// Panics if the result is negative or overflows
+impl core::ops::Sub<SignedAmount> for Amount {
+    type Output = Amount;
+
+    #[inline]
+    fn sub(self, rhs: SignedAmount) -> Self::Output {
+        self.checked_add_signed(-rhs).unwrap_or_else(|| panic!("subtracting amounts {} - {} is out of range", self, rhs))
+    }
+}
+
+/// Panics if the result is negative or overflows
+impl core::ops::SubAssign<SignedAmount> for Amount {
+    #[inline]
+    fn sub_assign(&mut self, rhs: SignedAmount) {
+        *self = *self - rhs;
+    }
+}
+
+/// Panics on overflow
+impl core::iter::Sum for SignedAmount {
+    fn sum<I: Iterator<Item = SignedAmount>>(iter: I) -> Self {
+        iter.fold(SignedAmount::ZERO, core::ops::Add::add)
     }
 }
 
-impl core::ops::Rem<u64> for Amount {
-    type Output = Self;
+/// Panics on overflow
+impl<'a> core::iter::Sum<&'a SignedAmount> for SignedAmount {
+    fn sum<I: Iterator<Item = &'a SignedAmount>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
 
-    fn rem(self, rhs: u64) -> Self::Output {
-        Amount(self.0 % rhs)
+/// Returns `None` on overflow
+impl core::iter::Sum<SignedAmount> for Option<SignedAmount> {
+    fn sum<I: Iterator<Item = SignedAmount>>(mut iter: I) -> Self {
+        iter.try_fold(SignedAmount::ZERO, SignedAmount::checked_add)
     }
 }
 
-impl core::ops::RemAssign<u64> for Amount {
-    fn rem_assign(&mut self, rhs: u64) {
-        *self = *self % rhs;
+/// Returns `None` on overflow
+impl<'a> core::iter::Sum<&'a SignedAmount> for Option<SignedAmount> {
+    fn sum<I: Iterator<Item = &'a SignedAmount>>(iter: I) -> Self {
+        iter.copied().sum()
     }
 }
 
-/// Accepts an unsigned integer up to 21 000 000 BTC
-/// The amount may optionally be followed by denomination ` msat`.
-impl FromStr for Amount {
+/// Accepts the same format as [`Amount`] optionally preceded by `+` or `-` sign.
+impl FromStr for SignedAmount {
     type Err = ParseError;
 
     #[inline]
@@ -326,9 +1927,8 @@ impl FromStr for Amount {
     }
 }
 
-/// Accepts an unsigned integer up to 21 000 000 BTC
-/// The amount may optionally be followed by denomination ` msat`.
-impl<'a> TryFrom<&'a str> for Amount {
+/// Accepts the same format as [`Amount`] optionally preceded by `+` or `-` sign.
+impl<'a> TryFrom<&'a str> for SignedAmount {
     type Error = ParseError;
 
     #[inline]
@@ -337,10 +1937,9 @@ impl<'a> TryFrom<&'a str> for Amount {
     }
 }
 
-/// Accepts an unsigned integer up to 21 000 000 BTC
-/// The amount may optionally be followed by denomination ` msat`.
+/// Accepts the same format as [`Amount`] optionally preceded by `+` or `-` sign.
 #[cfg(feature = "alloc")]
-impl TryFrom<String> for Amount {
+impl TryFrom<String> for SignedAmount {
     type Error = ParseError;
 
     #[inline]
@@ -349,10 +1948,9 @@ impl TryFrom<String> for Amount {
     }
 }
 
-/// Accepts an unsigned integer up to 21 000 000 BTC
-/// The amount may optionally be followed by denomination ` msat`.
+/// Accepts the same format as [`Amount`] optionally preceded by `+` or `-` sign.
 #[cfg(feature = "alloc")]
-impl TryFrom<Box<str>> for Amount {
+impl TryFrom<Box<str>> for SignedAmount {
     type Error = ParseError;
 
     #[inline]
@@ -393,6 +1991,13 @@ impl std::error::Error for ParseError {
 #[derive(Debug, Clone)]
 enum ParseErrorInner {
     ParseInt(core::num::ParseIntError),
+    DoubleSign,
+    UnexpectedSign,
+    UnknownDenomination,
+    DenominationMismatch { expected: Denomination, },
+    EmptyFraction,
+    FractionChar { pos: usize, c: char, },
+    TooPrecise,
     Overflow(OverflowError),
 }
 
@@ -412,6 +2017,14 @@ impl fmt::Display for ParseErrorInner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ParseErrorInner::ParseInt(error) => write_err!(f, "invalid integer"; error),
+            ParseErrorInner::DoubleSign => f.write_str("multiple signs"),
+            ParseErrorInner::UnexpectedSign => f.write_str("unexpected sign (the amount must not be signed)"),
+            ParseErrorInner::UnknownDenomination => f.write_str("unknown denomination (expected btc, sat or msat)"),
+            ParseErrorInner::DenominationMismatch { expected, } => write!(f, "denomination doesn't match the expected {}", expected),
+            ParseErrorInner::EmptyFraction => f.write_str("missing digits after decimal point"),
+            ParseErrorInner::FractionChar { pos, c, } => write!(f, "invalid character '{}' at position {} (must be a decimal digit)", c, pos),
+            ParseErrorInner::TooPrecise => f.write_str("the value has a sub-millisatoshi precision"),
+            ParseErrorInner::Overflow(error) if error.negative => write_err!(f, "value below negative supply cap"; error),
             ParseErrorInner::Overflow(error) => write_err!(f, "value above supply cap"; error),
         }
     }
@@ -422,6 +2035,13 @@ impl std::error::Error for ParseErrorInner {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ParseErrorInner::ParseInt(error) => Some(error),
+            ParseErrorInner::DoubleSign
+            | ParseErrorInner::UnexpectedSign
+            | ParseErrorInner::UnknownDenomination
+            | ParseErrorInner::DenominationMismatch { .. }
+            | ParseErrorInner::EmptyFraction
+            | ParseErrorInner::FractionChar { .. }
+            | ParseErrorInner::TooPrecise => None,
             ParseErrorInner::Overflow(error) => Some(error),
         }
     }
@@ -434,11 +2054,16 @@ impl std::error::Error for ParseErrorInner {
 pub struct OverflowError {
     amount: u64,
     denomination: &'static str,
+    negative: bool,
 }
 
 impl fmt::Display for OverflowError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} exceeds the maximum number of 21 million bitcoins", self.amount, self.denomination)
+        if self.negative {
+            write!(f, "-{} {} is below the minimum of -21 million bitcoins", self.amount, self.denomination)
+        } else {
+            write!(f, "{} {} exceeds the maximum number of 21 million bitcoins", self.amount, self.denomination)
+        }
     }
 }
 
@@ -451,20 +2076,131 @@ impl std::error::Error for OverflowError {}
 #[derive(Debug, Clone)]
 pub struct FractionError {
     amount: u64,
+    negative: bool,
 }
 
 impl fmt::Display for FractionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} millisatoshis can not be converted to satoshis because it's not rounded to thousands", self.amount)
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}{} millisatoshis can not be converted to satoshis because it's not rounded to thousands", sign, self.amount)
     }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for FractionError {}
 
+/// Error returned when converting a negative [`SignedAmount`] to [`Amount`].
+///
+/// **Important: consumer code MUST NOT match on this using `NegativeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct NegativeError {
+    amount: SignedAmount,
+}
+
+impl fmt::Display for NegativeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} can not be converted to an unsigned amount because it's negative", self.amount)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NegativeError {}
+
+/// Error returned when an amount can not be split into parts of at least the given minimum.
+///
+/// **Important: consumer code MUST NOT match on this using `SplitError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct SplitError {
+    amount: Amount,
+    min: Amount,
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} can not be split into parts of at least {}", self.amount, self.min)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SplitError {}
+
+/// Error returned when parsing BOLT 11 human-readable amount fails.
+///
+/// **Important: consumer code MUST NOT match on this using `Bolt11ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct Bolt11ParseError {
+    #[cfg(feature = "alloc")]
+    input: String,
+    reason: Bolt11ParseErrorInner,
+}
+
+impl fmt::Display for Bolt11ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        {
+            write_err!(f, "failed to parse '{}' as BOLT 11 amount", self.input; &self.reason)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            write_err!(f, "failed to parse BOLT 11 amount"; &self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Bolt11ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Bolt11ParseErrorInner {
+    Empty,
+    MissingNumber,
+    UnknownMultiplier(char),
+    InvalidChar { pos: usize, c: char, },
+    LeadingZero,
+    TooLarge,
+    SubMillisatoshi(u128),
+    Overflow(OverflowError),
+}
+
+impl fmt::Display for Bolt11ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bolt11ParseErrorInner::Empty => f.write_str("empty string"),
+            Bolt11ParseErrorInner::MissingNumber => f.write_str("missing number before multiplier"),
+            Bolt11ParseErrorInner::UnknownMultiplier(c) => write!(f, "unknown multiplier '{}' (expected m, u, n or p)", c),
+            Bolt11ParseErrorInner::InvalidChar { pos, c, } => write!(f, "invalid character '{}' at position {} (must be a decimal digit)", c, pos),
+            Bolt11ParseErrorInner::LeadingZero => f.write_str("the number has leading zeros"),
+            Bolt11ParseErrorInner::TooLarge => f.write_str("the number is too large"),
+            Bolt11ParseErrorInner::SubMillisatoshi(amount) => write!(f, "{} pico-bitcoins is not a multiple of 10 and can not be represented in millisatoshis", amount),
+            Bolt11ParseErrorInner::Overflow(error) => write_err!(f, "value above supply cap"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Bolt11ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Bolt11ParseErrorInner::Empty
+            | Bolt11ParseErrorInner::MissingNumber
+            | Bolt11ParseErrorInner::UnknownMultiplier(_)
+            | Bolt11ParseErrorInner::InvalidChar { .. }
+            | Bolt11ParseErrorInner::LeadingZero
+            | Bolt11ParseErrorInner::TooLarge
+            | Bolt11ParseErrorInner::SubMillisatoshi(_) => None,
+            Bolt11ParseErrorInner::Overflow(error) => Some(error),
+        }
+    }
+}
+
 #[cfg(feature = "bitcoin-units")]
 mod impl_bitcoin {
-    use super::{Amount, OverflowError, FractionError};
+    use super::{Amount, SignedAmount, OverflowError, FractionError};
     use core::convert::TryFrom;
 
     impl TryFrom<bitcoin_units::Amount> for Amount {
@@ -482,16 +2218,38 @@ mod impl_bitcoin {
             Ok(Self::from_sat(value.to_sat()?))
         }
     }
+
+    impl TryFrom<bitcoin_units::SignedAmount> for SignedAmount {
+        type Error = OverflowError;
+
+        fn try_from(value: bitcoin_units::SignedAmount) -> Result<Self, Self::Error> {
+            Self::from_sat(value.to_sat())
+        }
+    }
+
+    impl TryFrom<SignedAmount> for bitcoin_units::SignedAmount {
+        type Error = FractionError;
+
+        fn try_from(value: SignedAmount) -> Result<Self, Self::Error> {
+            Ok(Self::from_sat(value.to_sat()?))
+        }
+    }
 }
 
 #[cfg(feature = "parse_arg")]
 mod parse_arg_impl {
     use core::fmt;
-    use super::Amount;
+    use super::{Amount, SignedAmount};
 
     impl parse_arg::ParseArgFromStr for Amount {
         fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
-            writer.write_str("millisatoshis - a non-negative integer up to 2 100 000 000 000 000 000")
+            writer.write_str("an amount - a non-negative decimal number up to 21 000 000 BTC followed by denomination btc, sat or msat (millisatoshis if omitted), e.g. 0.05btc, 2500 sats, 1.5 sat")
+        }
+    }
+
+    impl parse_arg::ParseArgFromStr for SignedAmount {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("a signed amount - a decimal number optionally preceded by + or - with absolute value up to 21 000 000 BTC followed by denomination btc, sat or msat (millisatoshis if omitted), e.g. -0.05btc, +2500 sats, 1.5 sat")
         }
     }
 }
@@ -499,7 +2257,7 @@ mod parse_arg_impl {
 #[cfg(feature = "serde")]
 mod serde_impl {
     use core::fmt;
-    use super::Amount;
+    use super::{Amount, SignedAmount};
     use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
 
     struct HRVisitor;
@@ -531,12 +2289,50 @@ mod serde_impl {
             deserializer.deserialize_u64(HRVisitor)
         }
     }
+
+    struct SignedVisitor;
+
+    impl<'de> Visitor<'de> for SignedVisitor {
+        type Value = SignedAmount;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("an integer between -2 100 000 000 000 000 000 and 2 100 000 000 000 000 000")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            SignedAmount::from_msat(v).map_err(|_| {
+                E::invalid_value(serde::de::Unexpected::Signed(v), &self)
+            })
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            Amount::from_msat(v)
+                .map(Into::into)
+                .map_err(|_| {
+                    E::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                })
+        }
+    }
+
+    /// The value is serialized as `i64` msats.
+    impl Serialize for SignedAmount {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            serializer.serialize_i64(self.0)
+        }
+    }
+
+    /// The value is deserialized as `i64` msats.
+    impl<'de> Deserialize<'de> for SignedAmount {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            deserializer.deserialize_i64(SignedVisitor)
+        }
+    }
 }
 
 #[cfg(feature = "postgres-types")]
 mod postgres_impl {
     use alloc::boxed::Box;
-    use super::Amount;
+    use super::{Amount, SignedAmount};
     use postgres_types::{ToSql, FromSql, IsNull, Type};
     use bytes::BytesMut;
     use std::error::Error;
@@ -569,12 +2365,37 @@ mod postgres_impl {
             <i64 as FromSql>::accepts(ty)
         }
     }
+
+    /// Stored as `i64` msats
+    impl ToSql for SignedAmount {
+        fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+            self.to_msat().to_sql(ty, out)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i64 as ToSql>::accepts(ty)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    /// Retrieved as `i64` msats with range check
+    impl<'a> FromSql<'a> for SignedAmount {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+            let msats = <i64>::from_sql(ty, raw)?;
+            SignedAmount::from_msat(msats).map_err(|error| Box::new(error) as _)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i64 as FromSql>::accepts(ty)
+        }
+    }
 }
 
 /// Implementations of `slog` traits
 #[cfg(feature = "slog")]
 mod slog_impl {
-    use super::Amount;
+    use super::{Amount, SignedAmount};
     use slog::{Key, Value, Record, Serializer};
 
     /// Logs msats using `emit_u64`
@@ -584,19 +2405,435 @@ mod slog_impl {
         }
     }
 
-    impl_error_value!(super::ParseError, super::OverflowError, super::FractionError);
+    /// Logs msats using `emit_i64`
+    impl Value for SignedAmount {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_i64(key, self.0)
+        }
+    }
+
+    impl_error_value!(super::ParseError, super::OverflowError, super::FractionError, super::NegativeError, super::SplitError, super::Bolt11ParseError);
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Amount;
+    use super::{Amount, SignedAmount, Denomination};
+    use alloc::{format, string::ToString};
+    use core::convert::TryFrom;
 
     #[test]
     fn amount_max() {
         assert_eq!(Amount::from_msat(super::MAX_MONEY_MSAT).unwrap(), Amount::MAX);
     }
 
+    #[test]
+    fn display_default() {
+        let amount = Amount::from_msat(1_234_500).unwrap();
+        assert_eq!(amount.to_string(), "1234500 msat");
+        assert_eq!(amount.display_in(Denomination::Sat).to_string(), "1234.5 sat");
+        assert_eq!(amount.display_in(Denomination::Btc).to_string(), "0.000012345 BTC");
+        assert_eq!(Amount::ZERO.display_in(Denomination::Btc).to_string(), "0 BTC");
+        assert_eq!(Amount::ONE_MSAT.display_in(Denomination::Btc).to_string(), "0.00000000001 BTC");
+        assert_eq!(Amount::MAX.display_in(Denomination::Btc).to_string(), "21000000 BTC");
+    }
+
+    #[test]
+    fn display_precision() {
+        let amount = Amount::from_msat(1_235).unwrap();
+        assert_eq!(format!("{:.0}", amount.display_in(Denomination::Sat)), "1 sat");
+        assert_eq!(format!("{:.1}", amount.display_in(Denomination::Sat)), "1.2 sat");
+        assert_eq!(format!("{:.2}", amount.display_in(Denomination::Sat)), "1.24 sat");
+        assert_eq!(format!("{:.5}", amount.display_in(Denomination::Sat)), "1.23500 sat");
+        assert_eq!(format!("{:.2}", amount.display_in(Denomination::Msat)), "1235.00 msat");
+        assert_eq!(format!("{:>12.2}", amount), "1235 msat");
+        assert_eq!(format!("{:.2}", Amount::from_msat(99_999_999_999).unwrap().display_in(Denomination::Btc)), "1.00 BTC");
+        assert_eq!(format!("{:.8}", Amount::from_msat(1_499).unwrap().display_in(Denomination::Btc)), "0.00000001 BTC");
+    }
+
+    #[test]
+    fn display_padding() {
+        let amount = Amount::from_msat(1_500).unwrap();
+        assert_eq!(format!("{:>10}", amount.display_in(Denomination::Sat)), "   1.5 sat");
+        assert_eq!(format!("{:10}", amount.display_in(Denomination::Sat)), "   1.5 sat");
+        assert_eq!(format!("{:<10}|", amount.display_in(Denomination::Sat)), "1.5 sat   |");
+        assert_eq!(format!("{:*^11}", amount.display_in(Denomination::Sat)), "**1.5 sat**");
+        assert_eq!(format!("{:08.2}", amount.display_in(Denomination::Sat).without_denomination()), "00001.50");
+        assert_eq!(format!("{:3}", amount.display_in(Denomination::Sat)), "1.5 sat");
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        let amounts = [0, 1, 999, 1_000, 1_001, 123_456_789, 100_000_000_000, super::MAX_MONEY_MSAT];
+        for &msat in &amounts {
+            let amount = Amount::from_msat(msat).unwrap();
+            for &denomination in &[Denomination::Btc, Denomination::Sat, Denomination::Msat] {
+                let displayed = amount.display_in(denomination).to_string();
+                assert_eq!(Amount::from_str_in(&displayed, denomination).unwrap(), amount);
+                let displayed = amount.display_in(denomination).without_denomination().to_string();
+                assert_eq!(Amount::from_str_in(&displayed, denomination).unwrap(), amount);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_in() {
+        assert_eq!(Amount::from_str_in("0.001 btc", Denomination::Btc).unwrap().to_msat(), 100_000_000);
+        assert_eq!(Amount::from_str_in("1.2340", Denomination::Sat).unwrap().to_msat(), 1_234);
+        assert_eq!(Amount::from_str_in("21000000", Denomination::Btc).unwrap(), Amount::MAX);
+        assert!(Amount::from_str_in("1.", Denomination::Sat).is_err());
+        assert!(Amount::from_str_in("1.2345", Denomination::Sat).is_err());
+        assert!(Amount::from_str_in("1.5", Denomination::Msat).is_err());
+        assert!(Amount::from_str_in("1.-5", Denomination::Sat).is_err());
+        assert!(Amount::from_str_in("1 BTC", Denomination::Sat).is_err());
+        assert!(Amount::from_str_in("1 btcs", Denomination::Btc).is_err());
+        assert!(Amount::from_str_in("+1", Denomination::Sat).is_err());
+        assert_eq!(Amount::from_str_in("1500sats", Denomination::Sat).unwrap().to_msat(), 1_500_000);
+        assert!(Amount::from_str_in("21000000.00000000001", Denomination::Btc).is_err());
+    }
+
+    #[test]
+    fn parse_in_errors() {
+        let error = Amount::from_str_in("1.2345", Denomination::Sat).unwrap_err();
+        assert!(matches!(error.reason, super::ParseErrorInner::TooPrecise));
+        let error = Amount::from_str_in("1.2#", Denomination::Sat).unwrap_err();
+        assert!(matches!(error.reason, super::ParseErrorInner::FractionChar { pos: 3, c: '#', }));
+        let error = Amount::from_str_in("1 BTC", Denomination::Sat).unwrap_err();
+        assert!(matches!(error.reason, super::ParseErrorInner::DenominationMismatch { expected: Denomination::Sat, }));
+        let error = Amount::from_str_in("+1 sat", Denomination::Sat).unwrap_err();
+        assert!(matches!(error.reason, super::ParseErrorInner::UnexpectedSign));
+        let error = Amount::from_str_in("300000000", Denomination::Btc).unwrap_err();
+        match error.reason {
+            super::ParseErrorInner::Overflow(error) => assert_eq!(error.to_string(), "300000000 bitcoins exceeds the maximum number of 21 million bitcoins"),
+            error => panic!("unexpected error: {:?}", error),
+        }
+        assert!(Amount::from_str_in("1\u{e9} sat", Denomination::Sat).is_err());
+    }
+
+    #[test]
+    fn parse_with_denomination() {
+        let cases = [
+            ("42", 42),
+            ("42 msat", 42),
+            ("42msats", 42),
+            ("42 MSAT", 42),
+            ("1500 sat", 1_500_000),
+            ("2500 sats", 2_500_000),
+            ("1.234 sat", 1_234),
+            ("1.5sat", 1_500),
+            ("0.05btc", 5_000_000_000),
+            ("0.001 BTC", 100_000_000),
+            ("0.00000000001 BTC", 1),
+            ("21000000 BTC", super::MAX_MONEY_MSAT),
+        ];
+        for &(input, msat) in &cases {
+            assert_eq!(input.parse::<Amount>().unwrap().to_msat(), msat, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let cases = ["", "sat", "1 s", "1 eur", "1.5", "1.5 msat", "1.2345 sat", "0.000000000001 btc", "1. sat", ".5 sat", "1 000 sat", "-1 sat", "1.5.5 sat", "5 btcs", "5 BTCS", "5 satss", "+5 sat", "+5", "-0", "5 s"];
+        for input in &cases {
+            assert!(input.parse::<Amount>().is_err(), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let half = Amount::from_msat(super::MAX_MONEY_MSAT / 2).unwrap();
+        assert_eq!(half.checked_add(half).unwrap(), Amount::MAX);
+        assert!(Amount::MAX.checked_add(Amount::ONE_MSAT).is_none());
+        assert_eq!(Amount::MAX.checked_sub(Amount::MAX).unwrap(), Amount::ZERO);
+        assert!(Amount::ZERO.checked_sub(Amount::ONE_MSAT).is_none());
+        assert_eq!(Amount::ONE_BTC.checked_mul(21_000_000).unwrap(), Amount::MAX);
+        assert!(Amount::ONE_BTC.checked_mul(21_000_001).is_none());
+        assert!(Amount::MAX.checked_mul(u64::MAX).is_none());
+        assert_eq!(Amount::ONE_SAT.checked_div(1000).unwrap(), Amount::ONE_MSAT);
+        assert!(Amount::ONE_SAT.checked_div(0).is_none());
+        assert_eq!(Amount::MAX.saturating_add(Amount::MAX), Amount::MAX);
+        assert_eq!(Amount::ZERO.saturating_sub(Amount::MAX), Amount::ZERO);
+        assert_eq!(Amount::ZERO.abs_diff(Amount::MAX), Amount::MAX);
+    }
+
+    #[test]
+    fn checked_arithmetic_is_const() {
+        const SUM: Option<Amount> = Amount::ONE_SAT.checked_add(Amount::ONE_MSAT);
+        assert_eq!(SUM.unwrap().to_msat(), 1001);
+    }
+
+    #[test]
+    fn sum() {
+        let amounts = [Amount::ONE_MSAT, Amount::ONE_SAT, Amount::ONE_BTC];
+        let expected = Amount::from_msat(100_000_001_001).unwrap();
+        assert_eq!(amounts.iter().sum::<Amount>(), expected);
+        assert_eq!(amounts.iter().copied().sum::<Amount>(), expected);
+        assert_eq!(amounts.iter().sum::<Option<Amount>>(), Some(expected));
+        assert_eq!(amounts.iter().copied().sum::<Option<Amount>>(), Some(expected));
+        assert_eq!(core::iter::empty::<Amount>().sum::<Amount>(), Amount::ZERO);
+        assert_eq!([Amount::MAX, Amount::ONE_MSAT].iter().sum::<Option<Amount>>(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sum_overflow() {
+        let _ = [Amount::MAX, Amount::ONE_MSAT].iter().sum::<Amount>();
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn bolt11_hrp_encode() {
+        let cases = [
+            (1, "10p"),
+            (15, "150p"),
+            (100, "1n"),
+            (1_000, "10n"),
+            (100_000, "1u"),
+            (250_000_000, "2500u"),
+            (2_000_000_000, "20m"),
+            (100_000_000_000, "1"),
+            (super::MAX_MONEY_MSAT, "21000000"),
+            (super::MAX_MONEY_MSAT - 1, "20999999999999999990p"),
+            (0, "0"),
+        ];
+        for &(msat, expected) in &cases {
+            let amount = Amount::from_msat(msat).unwrap();
+            assert_eq!(amount.to_bolt11_hrp_string(), expected);
+            assert_eq!(Amount::from_bolt11_hrp_str(expected).unwrap(), amount);
+        }
+    }
+
+    #[test]
+    fn bolt11_hrp_decode() {
+        assert_eq!(Amount::from_bolt11_hrp_str("2500000000n").unwrap(), Amount::from_sat(250_000_000).unwrap());
+        assert_eq!(Amount::from_bolt11_hrp_str("20M").unwrap(), Amount::from_sat(2_000_000).unwrap());
+        assert_eq!(Amount::from_bolt11_hrp_str("1000p").unwrap(), Amount::from_msat(100).unwrap());
+        for &input in &["", "m", "-1m", "+1m", "1x", "01m", "1.5m", "1mm", "1 m", "1\u{e9}", "21000001", "99999999999999999999"] {
+            assert!(Amount::from_bolt11_hrp_str(input).is_err(), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn bolt11_hrp_errors() {
+        let cases = [
+            ("", "empty string"),
+            ("u", "missing number before multiplier"),
+            ("1x", "unknown multiplier 'x' (expected m, u, n or p)"),
+            ("1.5m", "invalid character '.' at position 1 (must be a decimal digit)"),
+            ("01m", "the number has leading zeros"),
+            ("99999999999999999999", "the number is too large"),
+            ("15p", "15 pico-bitcoins is not a multiple of 10 and can not be represented in millisatoshis"),
+        ];
+        for &(input, expected) in &cases {
+            let error = Amount::from_bolt11_hrp_str(input).unwrap_err();
+            assert_eq!(error.reason.to_string(), expected);
+        }
+        let error = Amount::from_bolt11_hrp_str("21000001000m").unwrap_err();
+        match error.reason {
+            super::Bolt11ParseErrorInner::Overflow(error) => assert_eq!(error.to_string(), "21000001000 milli-bitcoins exceeds the maximum number of 21 million bitcoins"),
+            error => panic!("unexpected error: {:?}", error),
+        }
+        #[cfg(feature = "alloc")]
+        assert!(Amount::from_bolt11_hrp_str("1p").unwrap_err().to_string().starts_with("failed to parse '1p' as BOLT 11 amount"));
+    }
+
+    #[test]
+    fn split_even() {
+        let amount = Amount::from_msat(1_001).unwrap();
+        let parts = amount.split_even(4).map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [251, 250, 250, 250]);
+        assert_eq!(Amount::ONE_MSAT.split_even(3).map(Amount::to_msat).collect::<alloc::vec::Vec<_>>(), [1, 0, 0]);
+        assert_eq!(Amount::MAX.split_even(7).sum::<Amount>(), Amount::MAX);
+        assert_eq!(Amount::MAX.split_even(u64::MAX).take(3).count(), 3);
+        assert_eq!(amount.split_even(4).size_hint(), (4, Some(4)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_even_zero_parts() {
+        let _ = Amount::ONE_SAT.split_even(0);
+    }
+
+    #[test]
+    fn split_even_min_part() {
+        let amount = Amount::from_msat(1_000).unwrap();
+        let min = Amount::from_msat(300).unwrap();
+        let parts = amount.split_even_with_min(10, min).unwrap();
+        assert_eq!(parts.clone().count(), 3);
+        assert!(parts.clone().all(|part| part >= min));
+        assert_eq!(parts.sum::<Amount>(), amount);
+        assert_eq!(amount.split_even_with_min(2, min).unwrap().count(), 2);
+        assert_eq!(amount.split_even_with_min(2, Amount::ZERO).unwrap().count(), 2);
+        assert_eq!(Amount::ZERO.split_even_with_min(2, min).unwrap().count(), 0);
+        assert_eq!(amount.split_even_with_min(10, min).unwrap().size_hint(), (3, Some(3)));
+        let error = Amount::from_msat(299).unwrap().split_even_with_min(2, min).unwrap_err();
+        assert_eq!(error.to_string(), "299 msat can not be split into parts of at least 300 msat");
+    }
+
+    #[test]
+    fn split_weighted() {
+        let amount = Amount::from_msat(1_000).unwrap();
+        let parts = amount.split_weighted(&[1, 1, 1]).map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [333, 333, 334]);
+        let parts = amount.split_weighted(&[0, 3, 0, 1]).map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [0, 750, 0, 250]);
+        assert_eq!(Amount::MAX.split_weighted(&[u64::MAX, u64::MAX, 1]).sum::<Amount>(), Amount::MAX);
+        let parts = Amount::MAX.split_weighted(&[u64::MAX; 9]).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts.iter().sum::<Amount>(), Amount::MAX);
+        let expected = super::MAX_MONEY_MSAT / 9;
+        assert!(parts.iter().all(|part| part.to_msat() == expected || part.to_msat() == expected + 1));
+        let parts = Amount::MAX.split_weighted(&[u64::MAX, 0, u64::MAX - 1, u64::MAX, 1, u64::MAX, u64::MAX, u64::MAX, u64::MAX]).map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts.iter().sum::<u64>(), super::MAX_MONEY_MSAT);
+        assert_eq!(parts[1], 0);
+        let weights = [7, 13, 1, 0, 999, 2];
+        for &msat in &[0, 1, 5, 999, 1_001, 123_456_789, super::MAX_MONEY_MSAT] {
+            let amount = Amount::from_msat(msat).unwrap();
+            assert_eq!(amount.split_weighted(&weights).len(), weights.len());
+            assert_eq!(amount.split_weighted(&weights).sum::<Amount>(), amount);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_weighted_zero_weights() {
+        let _ = Amount::ONE_SAT.split_weighted(&[0, 0]);
+    }
+
+    #[test]
+    fn split_weighted_min_part() {
+        let amount = Amount::from_msat(1_000).unwrap();
+        let min = Amount::from_msat(400).unwrap();
+        let parts = amount.split_weighted_with_min(&[3, 1, 3, 3], min).unwrap().map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [500, 0, 500, 0]);
+        let parts = amount.split_weighted_with_min(&[1, 1], Amount::from_msat(501).unwrap()).unwrap().map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [1_000, 0]);
+        let parts = amount.split_weighted_with_min(&[1, 2], Amount::ZERO).unwrap().map(Amount::to_msat).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(parts, [333, 667]);
+        assert!(Amount::from_msat(399).unwrap().split_weighted_with_min(&[1], min).is_err());
+    }
+
+    #[test]
+    fn signed_bounds() {
+        assert_eq!(SignedAmount::from_msat(super::MAX_MONEY_MSAT as i64).unwrap(), SignedAmount::MAX);
+        assert_eq!(SignedAmount::from_msat(-(super::MAX_MONEY_MSAT as i64)).unwrap(), SignedAmount::MIN);
+        assert!(SignedAmount::from_msat(super::MAX_MONEY_MSAT as i64 + 1).is_err());
+        assert!(SignedAmount::from_msat(i64::MIN).is_err());
+        assert!(SignedAmount::from_sat(-(super::MAX_MONEY_SAT as i64) - 1).is_err());
+        assert_eq!(SignedAmount::from_msat(-1500).unwrap().to_sat().unwrap_err().to_string(), "-1500 millisatoshis can not be converted to satoshis because it's not rounded to thousands");
+        assert_eq!(SignedAmount::MIN.unsigned_abs(), Amount::MAX);
+        assert_eq!(-SignedAmount::MIN, SignedAmount::MAX);
+    }
+
+    #[test]
+    fn signed_conversions() {
+        assert_eq!(SignedAmount::from(Amount::MAX), SignedAmount::MAX);
+        assert_eq!(Amount::try_from(SignedAmount::ONE_SAT).unwrap(), Amount::ONE_SAT);
+        assert_eq!(Amount::try_from(SignedAmount::ZERO).unwrap(), Amount::ZERO);
+        let error = Amount::try_from(-SignedAmount::ONE_SAT).unwrap_err();
+        assert_eq!(error.to_string(), "-1000 msat can not be converted to an unsigned amount because it's negative");
+    }
+
+    #[test]
+    fn signed_arithmetic() {
+        let one = SignedAmount::ONE_SAT;
+        assert_eq!(one - one - one, -one);
+        assert_eq!(Amount::ONE_SAT + (-one), Amount::ZERO);
+        assert_eq!(Amount::ZERO - (-one), Amount::ONE_SAT);
+        assert_eq!(-one + Amount::ONE_SAT, SignedAmount::ZERO);
+        assert_eq!(SignedAmount::ZERO - Amount::MAX, SignedAmount::MIN);
+        assert_eq!(Amount::ZERO.signed_sub(Amount::MAX), SignedAmount::MIN);
+        assert_eq!(Amount::MAX.signed_sub(Amount::ZERO), SignedAmount::MAX);
+        assert_eq!(one * -3, SignedAmount::from_sat(-3).unwrap());
+        assert!(SignedAmount::MAX.checked_add(SignedAmount::ONE_MSAT).is_none());
+        assert!(SignedAmount::MIN.checked_mul(-1).is_some());
+        assert!(SignedAmount::MIN.checked_mul(2).is_none());
+        assert!(SignedAmount::ONE_MSAT.checked_mul(i64::MIN).is_none());
+        assert_eq!(SignedAmount::MIN.saturating_sub(one), SignedAmount::MIN);
+        assert_eq!(SignedAmount::MAX.saturating_add(one), SignedAmount::MAX);
+        assert_eq!(Amount::MAX.checked_add_signed(SignedAmount::MIN), Some(Amount::ZERO));
+        assert!(Amount::MAX.checked_add_signed(SignedAmount::ONE_MSAT).is_none());
+        assert_eq!([one, -one, -one].iter().sum::<SignedAmount>(), -one);
+        assert!([SignedAmount::MIN, -one, one].iter().sum::<Option<SignedAmount>>().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn signed_add_to_unsigned_underflow() {
+        let _ = Amount::ZERO + (-SignedAmount::ONE_MSAT);
+    }
+
+    #[test]
+    fn signed_display() {
+        let amount = SignedAmount::from_msat(-1_500).unwrap();
+        assert_eq!(amount.to_string(), "-1500 msat");
+        assert_eq!((-amount).to_string(), "+1500 msat");
+        assert_eq!(SignedAmount::ZERO.to_string(), "0 msat");
+        assert_eq!(format!("{:?}", amount), "-1500 msat");
+        assert_eq!(amount.display_in(Denomination::Sat).to_string(), "-1.5 sat");
+        assert_eq!(format!("{:>10}", amount.display_in(Denomination::Sat)), "  -1.5 sat");
+        assert_eq!(format!("{:<10}|", (-amount).display_in(Denomination::Sat)), "+1.5 sat  |");
+        assert_eq!(format!("{:08.2}", amount.display_in(Denomination::Sat).without_denomination()), "-0001.50");
+        assert_eq!(format!("{:.0}", SignedAmount::from_msat(-400).unwrap().display_in(Denomination::Sat)), "-0 sat");
+        assert_eq!(format!("{:+}", Amount::ONE_SAT.display_in(Denomination::Sat)), "+1 sat");
+        assert_eq!(SignedAmount::MIN.display_in(Denomination::Btc).to_string(), "-21000000 BTC");
+    }
+
+    #[test]
+    fn signed_parse() {
+        let cases = [
+            ("42", 42),
+            ("+42", 42),
+            ("-42", -42),
+            ("-0", 0),
+            ("-1.5 sat", -1_500),
+            ("+0.05btc", 5_000_000_000),
+            ("-21000000 BTC", -(super::MAX_MONEY_MSAT as i64)),
+        ];
+        for &(input, msat) in &cases {
+            assert_eq!(input.parse::<SignedAmount>().unwrap().to_msat(), msat, "input: {}", input);
+        }
+        for &input in &["", "-", "+-1", "--1", "- 1", "1-", "-21000000.00000000001 btc"] {
+            assert!(input.parse::<SignedAmount>().is_err(), "input: {}", input);
+        }
+        assert_eq!(SignedAmount::from_str_in("-0.001", Denomination::Btc).unwrap(), SignedAmount::from_sat(-100_000).unwrap());
+    }
+
+    #[test]
+    fn signed_display_roundtrip() {
+        let amounts = [0, 1, -1, 999, -1_001, 123_456_789, -100_000_000_000, super::MAX_MONEY_MSAT as i64, -(super::MAX_MONEY_MSAT as i64)];
+        for &msat in &amounts {
+            let amount = SignedAmount::from_msat(msat).unwrap();
+            assert_eq!(amount.to_string().parse::<SignedAmount>().unwrap(), amount);
+            for &denomination in &[Denomination::Btc, Denomination::Sat, Denomination::Msat] {
+                let displayed = amount.display_in(denomination).to_string();
+                assert_eq!(SignedAmount::from_str_in(&displayed, denomination).unwrap(), amount);
+            }
+        }
+    }
+
     chk_err_impl! {
+        parse_amount_error_unknown_denomination, "1 eur", Amount, [
+            "failed to parse '1 eur' as millisatoshis",
+            "unknown denomination (expected btc, sat or msat)",
+        ], [
+            "failed to parse millisatoshis",
+            "unknown denomination (expected btc, sat or msat)",
+        ];
+        parse_amount_error_too_precise, "1.2345 sat", Amount, [
+            "failed to parse '1.2345 sat' as millisatoshis",
+            "the value has a sub-millisatoshi precision",
+        ], [
+            "failed to parse millisatoshis",
+            "the value has a sub-millisatoshi precision",
+        ];
+        parse_amount_error_btc_overflow, "21000000.00000000001 btc", Amount, [
+            "failed to parse '21000000.00000000001 btc' as millisatoshis",
+            "value above supply cap",
+            "2100000000000000001 millisatoshis exceeds the maximum number of 21 million bitcoins"
+        ], [
+            "failed to parse millisatoshis",
+            "value above supply cap",
+            "2100000000000000001 millisatoshis exceeds the maximum number of 21 million bitcoins"
+        ];
         parse_amount_error_empty, "", Amount, ["failed to parse '' as millisatoshis", "invalid integer", "cannot parse integer from empty string"], ["failed to parse millisatoshis", "invalid integer", "cannot parse integer from empty string"];
         parse_amount_error_overflow, "2100000000000000001", Amount, [
             "failed to parse '2100000000000000001' as millisatoshis",
@@ -607,5 +2844,17 @@ mod tests {
             "value above supply cap",
             "2100000000000000001 millisatoshis exceeds the maximum number of 21 million bitcoins"
         ];
+        parse_signed_amount_error_underflow, "-21000000.00000000001 btc", SignedAmount, [
+            "failed to parse '-21000000.00000000001 btc' as millisatoshis",
+            "value below negative supply cap",
+            "-2100000000000000001 millisatoshis is below the minimum of -21 million bitcoins"
+        ], [
+            "failed to parse millisatoshis",
+            "value below negative supply cap",
+            "-2100000000000000001 millisatoshis is below the minimum of -21 million bitcoins"
+        ];
+        parse_amount_error_sign, "+5 sat", Amount, ["failed to parse '+5 sat' as millisatoshis", "unexpected sign (the amount must not be signed)"], ["failed to parse millisatoshis", "unexpected sign (the amount must not be signed)"];
+        parse_amount_error_plural_btc, "5 btcs", Amount, ["failed to parse '5 btcs' as millisatoshis", "unknown denomination (expected btc, sat or msat)"], ["failed to parse millisatoshis", "unknown denomination (expected btc, sat or msat)"];
+        parse_signed_amount_error_double_sign, "+-1", SignedAmount, ["failed to parse '+-1' as millisatoshis", "multiple signs"], ["failed to parse millisatoshis", "multiple signs"];
     }
 }
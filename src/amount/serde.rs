@@ -0,0 +1,676 @@
+//! Alternative serde representations of [`Amount`].
+//!
+//! The default implementation of `Serialize` and `Deserialize` for [`Amount`] uses `u64`
+//! millisatoshis. However various APIs use different representations so this module provides
+//! adapters to be used with `#[serde(with = "...")]` attribute:
+//!
+//! * [`as_str_msat`] - millisatoshis as a decimal string, e.g. `"1000"` (used by LND REST API)
+//! * [`cln_msat`] - millisatoshis as a string with `msat` suffix, e.g. `"1000msat"` (used by older
+//!   versions of Core Lightning)
+//! * [`as_sat`] - `u64` satoshis
+//! * [`as_btc`] - bitcoins as a decimal number, e.g. `0.001`
+//!
+//! Note that [`as_btc`] goes through `f64` which can not represent all amounts exactly. Amounts
+//! that can not be represented fail to serialize rather than silently changing their value, see
+//! its documentation for details.
+//!
+//! Each module contains `opt` submodule handling `Option<Amount>` which also works with
+//! `#[serde(default)]`.
+//!
+//! ## Example
+//!
+//! ```
+//! use ln_types::Amount;
+//! use serde_derive::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Channel {
+//!     #[serde(with = "ln_types::amount::serde::as_str_msat")]
+//!     local_balance: Amount,
+//!     #[serde(default, with = "ln_types::amount::serde::as_str_msat::opt")]
+//!     remote_balance: Option<Amount>,
+//! }
+//!
+//! let channel = serde_json::from_str::<Channel>(r#"{ "local_balance": "1000" }"#).unwrap();
+//! assert_eq!(channel.local_balance, Amount::ONE_SAT);
+//! assert_eq!(channel.remote_balance, None);
+//! ```
+
+use core::fmt;
+use super::{Amount, Denomination};
+use ::serde::de::{Deserializer, Error, Unexpected, Visitor};
+use ::serde::ser::Serializer;
+
+/// Implements `opt` submodule in terms of `serialize` and `deserialize` of the parent module.
+macro_rules! impl_opt {
+    ($module:literal) => {
+        /// Handles `Option<Amount>`.
+        ///
+        /// `None` is represented as `null` (or whatever the format uses for missing values).
+        /// Combine with `#[serde(default)]` to accept missing fields.
+        pub mod opt {
+            use core::fmt;
+            use crate::amount::Amount;
+            use ::serde::de::{Deserializer, Visitor, Error};
+            use ::serde::ser::{Serialize, Serializer};
+
+            struct Wrapper<'a>(&'a Amount);
+
+            impl<'a> Serialize for Wrapper<'a> {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+                    super::serialize(self.0, serializer)
+                }
+            }
+
+            struct OptVisitor;
+
+            impl<'de> Visitor<'de> for OptVisitor {
+                type Value = Option<Amount>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(formatter, "an optional amount serialized using {}", $module)
+                }
+
+                fn visit_none<E>(self) -> Result<Self::Value, E> where E: Error {
+                    Ok(None)
+                }
+
+                fn visit_unit<E>(self) -> Result<Self::Value, E> where E: Error {
+                    Ok(None)
+                }
+
+                fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: Deserializer<'de> {
+                    super::deserialize(deserializer).map(Some)
+                }
+            }
+
+            /// Serializes `Option<Amount>`.
+            pub fn serialize<S>(amount: &Option<Amount>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+                match amount {
+                    Some(amount) => serializer.serialize_some(&Wrapper(amount)),
+                    None => serializer.serialize_none(),
+                }
+            }
+
+            /// Deserializes `Option<Amount>`.
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Amount>, D::Error> where D: Deserializer<'de> {
+                deserializer.deserialize_option(OptVisitor)
+            }
+        }
+    }
+}
+
+/// Parses millisatoshis from a string of decimal digits.
+fn parse_msat_str<E: Error>(s: &str, expected: &dyn ::serde::de::Expected) -> Result<Amount, E> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(E::invalid_value(Unexpected::Str(s), expected));
+    }
+    let msat = s.parse::<u64>().map_err(|_| E::invalid_value(Unexpected::Str(s), expected))?;
+    Amount::from_msat(msat).map_err(E::custom)
+}
+
+/// Converts an integer obtained from self-describing formats.
+fn msat_from_i64<E: Error>(msat: i64, expected: &dyn ::serde::de::Expected) -> Result<Amount, E> {
+    if msat < 0 {
+        return Err(E::invalid_value(Unexpected::Signed(msat), expected));
+    }
+    Amount::from_msat(msat as u64).map_err(E::custom)
+}
+
+/// Millisatoshis serialized as a string of decimal digits.
+///
+/// This is used by LND REST API because JSON can not represent all 64-bit integers in some
+/// languages.
+/// Numbers are accepted too when deserializing from human-readable formats.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::Amount;
+/// use serde_derive::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Invoice {
+///     #[serde(with = "ln_types::amount::serde::as_str_msat")]
+///     value_msat: Amount,
+/// }
+///
+/// let invoice = serde_json::from_str::<Invoice>(r#"{ "value_msat": "2500" }"#).unwrap();
+/// assert_eq!(invoice.value_msat.to_msat(), 2500);
+/// assert_eq!(serde_json::to_string(&invoice).unwrap(), r#"{"value_msat":"2500"}"#);
+/// ```
+pub mod as_str_msat {
+    use super::*;
+
+    struct MsatStrVisitor;
+
+    impl<'de> Visitor<'de> for MsatStrVisitor {
+        type Value = Amount;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a string containing a non-negative integer up to 2 100 000 000 000 000 000")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            parse_msat_str(v, &self)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            Amount::from_msat(v).map_err(E::custom)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            msat_from_i64(v, &self)
+        }
+    }
+
+    /// Serializes the amount as a string of decimal digits.
+    pub fn serialize<S>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(&amount.to_msat())
+    }
+
+    /// Deserializes the amount from a string of decimal digits (or number in human-readable
+    /// formats).
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Amount, D::Error> where D: Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(MsatStrVisitor)
+        } else {
+            deserializer.deserialize_str(MsatStrVisitor)
+        }
+    }
+
+    impl_opt!("as_str_msat");
+}
+
+/// Millisatoshis serialized as a string with `msat` suffix.
+///
+/// This is used by Core Lightning before version 23.05.
+/// Plain numbers (used by newer versions) are accepted too when deserializing from human-readable
+/// formats.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::Amount;
+/// use serde_derive::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Funds {
+///     #[serde(with = "ln_types::amount::serde::cln_msat")]
+///     our_amount_msat: Amount,
+/// }
+///
+/// let funds = serde_json::from_str::<Funds>(r#"{ "our_amount_msat": "1000msat" }"#).unwrap();
+/// assert_eq!(funds.our_amount_msat, Amount::ONE_SAT);
+/// assert_eq!(serde_json::to_string(&funds).unwrap(), r#"{"our_amount_msat":"1000msat"}"#);
+/// ```
+pub mod cln_msat {
+    use super::*;
+
+    struct ClnVisitor;
+
+    impl<'de> Visitor<'de> for ClnVisitor {
+        type Value = Amount;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a string containing a non-negative integer up to 2 100 000 000 000 000 000 followed by 'msat'")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            match v.strip_suffix("msat") {
+                Some(msat) => parse_msat_str(msat, &self),
+                None => Err(E::invalid_value(Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            Amount::from_msat(v).map_err(E::custom)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            msat_from_i64(v, &self)
+        }
+    }
+
+    /// Serializes the amount as a string with `msat` suffix.
+    pub fn serialize<S>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(&format_args!("{}msat", amount.to_msat()))
+    }
+
+    /// Deserializes the amount from a string with `msat` suffix (or number in human-readable
+    /// formats).
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Amount, D::Error> where D: Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ClnVisitor)
+        } else {
+            deserializer.deserialize_str(ClnVisitor)
+        }
+    }
+
+    impl_opt!("cln_msat");
+}
+
+/// Satoshis serialized as `u64`.
+///
+/// Serialization fails if the amount is not a whole number of satoshis.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::Amount;
+/// use serde_derive::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Channel {
+///     #[serde(with = "ln_types::amount::serde::as_sat")]
+///     capacity: Amount,
+/// }
+///
+/// let channel = serde_json::from_str::<Channel>(r#"{ "capacity": 100000 }"#).unwrap();
+/// assert_eq!(channel.capacity, Amount::from_sat(100_000).unwrap());
+/// assert_eq!(serde_json::to_string(&channel).unwrap(), r#"{"capacity":100000}"#);
+/// ```
+pub mod as_sat {
+    use super::*;
+
+    struct SatVisitor;
+
+    impl<'de> Visitor<'de> for SatVisitor {
+        type Value = Amount;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a non-negative integer up to 2 100 000 000 000 000")
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            Amount::from_sat(v).map_err(E::custom)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            if v < 0 {
+                return Err(E::invalid_value(Unexpected::Signed(v), &self));
+            }
+            Amount::from_sat(v as u64).map_err(E::custom)
+        }
+    }
+
+    /// Serializes the amount as `u64` satoshis.
+    ///
+    /// ## Errors
+    ///
+    /// This fails if the amount is not a whole number of satoshis.
+    pub fn serialize<S>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        use ::serde::ser::Error;
+
+        serializer.serialize_u64(amount.to_sat().map_err(S::Error::custom)?)
+    }
+
+    /// Deserializes the amount from `u64` satoshis.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Amount, D::Error> where D: Deserializer<'de> {
+        deserializer.deserialize_u64(SatVisitor)
+    }
+
+    impl_opt!("as_sat");
+}
+
+/// Bitcoins serialized as a floating point number.
+///
+/// This is the representation used by Bitcoin Core and many other APIs.
+/// The conversion goes through the shortest decimal representation of the number so it's
+/// exact for numbers with at most 15 significant digits.
+/// Integers and decimal strings are accepted too when deserializing from human-readable formats.
+///
+/// ## Limitations
+///
+/// `f64` can not represent all amounts, e.g. `Amount::MAX - 1 msat` would become `Amount::MAX`.
+/// Serializing such amounts returns an error instead of changing the value.
+///
+/// When deserializing, numbers that are off by a few units in the last place from a millisatoshi
+/// amount are rounded to it. This makes values produced by floating point arithmetic (e.g.
+/// `0.30000000000000004`) acceptable while numbers with truly sub-millisatoshi precision are
+/// still rejected.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::Amount;
+/// use serde_derive::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Output {
+///     #[serde(with = "ln_types::amount::serde::as_btc")]
+///     value: Amount,
+/// }
+///
+/// let output = serde_json::from_str::<Output>(r#"{ "value": 0.00150000 }"#).unwrap();
+/// assert_eq!(output.value, Amount::from_sat(150_000).unwrap());
+/// assert_eq!(serde_json::to_string(&output).unwrap(), r#"{"value":0.0015}"#);
+/// ```
+pub mod as_btc {
+    use super::*;
+
+    /// Fixed-size buffer so that no allocation is needed.
+    struct Buffer {
+        bytes: [u8; 64],
+        len: usize,
+    }
+
+    impl Buffer {
+        fn new() -> Self {
+            Buffer {
+                bytes: [0; 64],
+                len: 0,
+            }
+        }
+
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).expect("only strings are written")
+        }
+    }
+
+    impl fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+            self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    /// Maximum distance between a float and the closest millisatoshi amount to be rounded.
+    const MAX_ROUNDING_ULPS: u64 = 4;
+
+    /// Converts the amount to the closest `f64` bitcoins.
+    fn to_f64(amount: Amount) -> f64 {
+        use core::fmt::Write;
+
+        let mut buffer = Buffer::new();
+        write!(buffer, "{}", amount.display_in(Denomination::Btc).without_denomination())
+            .expect("the buffer is large enough");
+        buffer.as_str().parse().expect("decimal numbers are valid floats")
+    }
+
+    /// Converts `f64` bitcoins to the amount, rounding off floating point errors.
+    ///
+    /// Returns `Err(None)` if the float is too large to be formatted.
+    fn from_f64(btc: f64) -> Result<Amount, Option<crate::amount::ParseError>> {
+        use core::fmt::Write;
+
+        // Display prints the shortest representation that roundtrips, without an exponent.
+        let mut buffer = Buffer::new();
+        write!(buffer, "{}", btc).map_err(|_| None)?;
+        let error = match Amount::from_str_in(buffer.as_str(), Denomination::Btc) {
+            Err(error) if matches!(error.reason, crate::amount::ParseErrorInner::TooPrecise) => error,
+            result => return result.map_err(Some),
+        };
+
+        // The float is not the closest one to any amount, which is usually caused by
+        // arithmetic. Round it if it's close enough.
+        let mut buffer = Buffer::new();
+        write!(buffer, "{:.11}", btc).map_err(|_| None)?;
+        let rounded = Amount::from_str_in(buffer.as_str(), Denomination::Btc).map_err(Some)?;
+        // Bits of non-negative floats are ordered the same way as the floats.
+        let ulps = (to_f64(rounded).to_bits() as i64 - btc.to_bits() as i64).unsigned_abs();
+        if ulps > MAX_ROUNDING_ULPS {
+            return Err(Some(error));
+        }
+        Ok(rounded)
+    }
+
+    struct BtcVisitor;
+
+    impl<'de> Visitor<'de> for BtcVisitor {
+        type Value = Amount;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a non-negative number of bitcoins up to 21 000 000")
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> where E: Error {
+            if !v.is_finite() || v < 0.0 {
+                return Err(E::invalid_value(Unexpected::Float(v), &self));
+            }
+            from_f64(v).map_err(|error| match error {
+                Some(error) => E::custom(error),
+                None => E::invalid_value(Unexpected::Float(v), &self),
+            })
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            v.checked_mul(super::super::SATS_IN_BTC)
+                .and_then(|sat| Amount::from_sat(sat).ok())
+                .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            if v < 0 {
+                return Err(E::invalid_value(Unexpected::Signed(v), &self));
+            }
+            self.visit_u64(v as u64)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            Amount::from_str_in(v, Denomination::Btc).map_err(E::custom)
+        }
+    }
+
+    /// Serializes the amount as `f64` bitcoins.
+    ///
+    /// ## Errors
+    ///
+    /// This fails if the amount can not be deserialized back from `f64` exactly.
+    pub fn serialize<S>(amount: &Amount, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        use ::serde::ser::Error;
+
+        let btc = to_f64(*amount);
+        if from_f64(btc).ok() != Some(*amount) {
+            return Err(S::Error::custom(format_args!("{} can not be represented as f64 bitcoins exactly", amount)));
+        }
+        serializer.serialize_f64(btc)
+    }
+
+    /// Deserializes the amount from `f64` bitcoins (or integer or decimal string in
+    /// human-readable formats).
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Amount, D::Error> where D: Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BtcVisitor)
+        } else {
+            deserializer.deserialize_f64(BtcVisitor)
+        }
+    }
+
+    impl_opt!("as_btc");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amount::Amount;
+    use serde_derive::{Serialize, Deserialize};
+
+    /// Sample of `lncli`/LND REST `channelbalance` response.
+    const LND_CHANNEL_BALANCE: &str = r#"{
+        "balance": "7896",
+        "pending_open_balance": "0",
+        "local_balance": { "sat": "7896", "msat": "7896000" },
+        "remote_balance": { "sat": "2104", "msat": "2104123" },
+        "unsettled_local_balance": { "sat": "0", "msat": "0" }
+    }"#;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct LndAmount {
+        #[serde(with = "super::as_str_msat")]
+        msat: Amount,
+    }
+
+    #[derive(Deserialize)]
+    struct LndChannelBalance {
+        local_balance: LndAmount,
+        remote_balance: LndAmount,
+        unsettled_local_balance: LndAmount,
+    }
+
+    #[test]
+    fn as_str_msat_lnd() {
+        let balance = serde_json::from_str::<LndChannelBalance>(LND_CHANNEL_BALANCE).unwrap();
+        assert_eq!(balance.local_balance.msat.to_msat(), 7_896_000);
+        assert_eq!(balance.remote_balance.msat.to_msat(), 2_104_123);
+        assert_eq!(balance.unsettled_local_balance.msat, Amount::ZERO);
+        assert_eq!(serde_json::to_string(&balance.remote_balance).unwrap(), r#"{"msat":"2104123"}"#);
+        assert_eq!(serde_json::from_str::<LndAmount>(r#"{"msat":42}"#).unwrap().msat.to_msat(), 42);
+        for &invalid in &[r#"{"msat":""}"#, r#"{"msat":"+42"}"#, r#"{"msat":"-42"}"#, r#"{"msat":-42}"#, r#"{"msat":"42 msat"}"#, r#"{"msat":"2100000000000000001"}"#] {
+            assert!(serde_json::from_str::<LndAmount>(invalid).is_err(), "input: {}", invalid);
+        }
+    }
+
+    /// Sample of older Core Lightning `listfunds` channel entry.
+    const CLN_FUNDS_CHANNEL: &str = r#"{
+        "peer_id": "022d223620a359a47ff7f7ac447c85c46c923da53389221a0054c11c1e3ca31d59",
+        "connected": true,
+        "state": "CHANNELD_NORMAL",
+        "short_channel_id": "103x1x0",
+        "our_amount_msat": "980000000msat",
+        "amount_msat": "1000000000msat",
+        "funding_txid": "8ef2e3bc1b4f3f2a1cb1c9b3f6f6c5f2d8a7e6b5c4d3e2f1a0b9c8d7e6f5a4b3",
+        "funding_output": 0
+    }"#;
+
+    #[derive(Deserialize)]
+    struct ClnFundsChannel {
+        #[serde(with = "super::cln_msat")]
+        our_amount_msat: Amount,
+        #[serde(with = "super::cln_msat")]
+        amount_msat: Amount,
+        #[serde(default, with = "super::cln_msat::opt")]
+        fee_msat: Option<Amount>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ClnAmount {
+        #[serde(default, with = "super::cln_msat::opt")]
+        amount_msat: Option<Amount>,
+    }
+
+    #[test]
+    fn cln_msat_listfunds() {
+        let channel = serde_json::from_str::<ClnFundsChannel>(CLN_FUNDS_CHANNEL).unwrap();
+        assert_eq!(channel.our_amount_msat, Amount::from_sat(980_000).unwrap());
+        assert_eq!(channel.amount_msat, Amount::from_sat(1_000_000).unwrap());
+        assert_eq!(channel.fee_msat, None);
+
+        let amount = ClnAmount { amount_msat: Some(Amount::ONE_SAT) };
+        assert_eq!(serde_json::to_string(&amount).unwrap(), r#"{"amount_msat":"1000msat"}"#);
+        assert_eq!(serde_json::from_str::<ClnAmount>(r#"{"amount_msat":"1000msat"}"#).unwrap(), amount);
+        // newer versions use plain integers
+        assert_eq!(serde_json::from_str::<ClnAmount>(r#"{"amount_msat":1000}"#).unwrap(), amount);
+        assert_eq!(serde_json::to_string(&ClnAmount { amount_msat: None }).unwrap(), r#"{"amount_msat":null}"#);
+        assert_eq!(serde_json::from_str::<ClnAmount>(r#"{"amount_msat":null}"#).unwrap().amount_msat, None);
+        assert_eq!(serde_json::from_str::<ClnAmount>("{}").unwrap().amount_msat, None);
+        for &invalid in &[r#"{"amount_msat":"1000"}"#, r#"{"amount_msat":"1000 msat"}"#, r#"{"amount_msat":"msat"}"#, r#"{"amount_msat":"1sat"}"#] {
+            assert!(serde_json::from_str::<ClnAmount>(invalid).is_err(), "input: {}", invalid);
+        }
+    }
+
+    /// Sample of older Core Lightning `listfunds` output entry.
+    const CLN_FUNDS_OUTPUT: &str = r#"{
+        "txid": "3511ae8a52c97d957eaf65f828504e68d0991f0276adff94c6ba91c7f6cd4275",
+        "output": 1,
+        "value": 250000,
+        "amount_msat": "250000000msat",
+        "address": "bcrt1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+        "status": "confirmed",
+        "blockheight": 103,
+        "reserved": false
+    }"#;
+
+    #[derive(Deserialize)]
+    struct ClnFundsOutput {
+        #[serde(deserialize_with = "super::as_sat::deserialize")]
+        value: Amount,
+        #[serde(default, with = "super::as_sat::opt")]
+        reserved_to_block: Option<Amount>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct SatAmount {
+        #[serde(with = "super::as_sat")]
+        amount: Amount,
+    }
+
+    #[test]
+    fn as_sat() {
+        let output = serde_json::from_str::<ClnFundsOutput>(CLN_FUNDS_OUTPUT).unwrap();
+        assert_eq!(output.value, Amount::from_sat(250_000).unwrap());
+        assert_eq!(output.reserved_to_block, None);
+
+        let amount = SatAmount { amount: Amount::from_sat(21).unwrap() };
+        assert_eq!(serde_json::to_string(&amount).unwrap(), r#"{"amount":21}"#);
+        assert_eq!(serde_json::from_str::<SatAmount>(r#"{"amount":21}"#).unwrap(), amount);
+        assert!(serde_json::to_string(&SatAmount { amount: Amount::ONE_MSAT }).is_err());
+        for &invalid in &[r#"{"amount":-1}"#, r#"{"amount":"21"}"#, r#"{"amount":2100000000000001}"#, r#"{"amount":1.5}"#] {
+            assert!(serde_json::from_str::<SatAmount>(invalid).is_err(), "input: {}", invalid);
+        }
+    }
+
+    /// Sample of Bitcoin Core `gettxout` response.
+    const CORE_TXOUT: &str = r#"{
+        "bestblock": "00000000000000000002a7c4c1e48d76c5a37902165a270156b7a8d72728a054",
+        "confirmations": 8,
+        "value": 0.01000000,
+        "scriptPubKey": {
+            "asm": "0 e8df018c7e326cc253faac7e46cdc51e68542c42",
+            "hex": "0014e8df018c7e326cc253faac7e46cdc51e68542c42",
+            "address": "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+            "type": "witness_v0_keyhash"
+        },
+        "coinbase": false
+    }"#;
+
+    #[derive(Deserialize)]
+    struct CoreTxOut {
+        #[serde(with = "super::as_btc")]
+        value: Amount,
+        #[serde(default, with = "super::as_btc::opt")]
+        fee: Option<Amount>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct BtcAmount {
+        #[serde(with = "super::as_btc")]
+        amount: Amount,
+    }
+
+    #[test]
+    fn as_btc() {
+        let txout = serde_json::from_str::<CoreTxOut>(CORE_TXOUT).unwrap();
+        assert_eq!(txout.value, Amount::from_sat(1_000_000).unwrap());
+        assert_eq!(txout.fee, None);
+
+        let cases = [
+            ("0.00000001", 1_000),
+            ("0.1", 10_000_000_000),
+            ("0.3", 30_000_000_000),
+            ("0.00000000001", 1),
+            ("1.23456789", 123_456_789_000),
+            ("20999999.99999999", super::super::MAX_MONEY_MSAT - 1_000),
+            ("21000000", super::super::MAX_MONEY_MSAT),
+        ];
+        for &(btc, msat) in &cases {
+            let json = alloc::format!(r#"{{"amount":{}}}"#, btc);
+            let amount = BtcAmount { amount: Amount::from_msat(msat).unwrap() };
+            assert_eq!(serde_json::from_str::<BtcAmount>(&json).unwrap(), amount, "input: {}", btc);
+            let serialized = serde_json::to_string(&amount).unwrap();
+            assert_eq!(serde_json::from_str::<BtcAmount>(&serialized).unwrap(), amount, "input: {}", btc);
+        }
+        assert_eq!(serde_json::to_string(&BtcAmount { amount: Amount::from_sat(123_456_789).unwrap() }).unwrap(), r#"{"amount":1.23456789}"#);
+        assert_eq!(serde_json::from_str::<BtcAmount>(r#"{"amount":"0.001"}"#).unwrap().amount, Amount::from_sat(100_000).unwrap());
+        assert_eq!(serde_json::from_str::<BtcAmount>(r#"{"amount":1e-8}"#).unwrap().amount, Amount::ONE_SAT);
+        assert_eq!(serde_json::from_str::<BtcAmount>(r#"{"amount":0.30000000000000004}"#).unwrap().amount, Amount::from_sat(30_000_000).unwrap());
+        assert_eq!(serde_json::from_str::<BtcAmount>(r#"{"amount":1.0000000000000002}"#).unwrap().amount, Amount::ONE_BTC);
+        let error = serde_json::to_string(&BtcAmount { amount: Amount::MAX - Amount::ONE_MSAT }).unwrap_err();
+        assert_eq!(alloc::string::ToString::to_string(&error), "2099999999999999999 msat can not be represented as f64 bitcoins exactly");
+        assert!(serde_json::to_string(&BtcAmount { amount: Amount::from_msat(123_456_789_012_345_678).unwrap() }).is_err());
+        for &invalid in &[r#"{"amount":-0.1}"#, r#"{"amount":21000000.00000001}"#, r#"{"amount":1e300}"#, r#"{"amount":1e-12}"#, r#"{"amount":0.3000000000001}"#, r#"{"amount":"abc"}"#] {
+            assert!(serde_json::from_str::<BtcAmount>(invalid).is_err(), "input: {}", invalid);
+        }
+    }
+}
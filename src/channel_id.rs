@@ -0,0 +1,585 @@
+//! Channel identifier
+//!
+//! This module provides the [`ChannelId`] type and the related error types.
+
+use core::convert::{TryFrom, TryInto};
+use core::str::FromStr;
+use core::fmt;
+use crate::NodeId;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// 32-byte identifier of a channel as used in BOLT 2 messages.
+///
+/// Unlike [`ShortChannelId`](crate::ShortChannelId) this identifier is known before the funding
+/// transaction confirms so it is commonly used to correlate channels between LND, Core Lightning
+/// and other implementations.
+///
+/// The identifier is displayed as hex in the same byte order as it's sent on the wire.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::ChannelId;
+///
+/// let mut txid = [0; 32];
+/// txid[31] = 0x42;
+/// let channel_id = ChannelId::from_funding_outpoint(txid, 3);
+/// assert_eq!(channel_id.to_string(), "0000000000000000000000000000000000000000000000000000000000000041");
+/// assert_eq!(channel_id.to_string().parse::<ChannelId>().unwrap(), channel_id);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ChannelId([u8; 32]);
+
+impl ChannelId {
+    /// Creates `ChannelId` from raw byte representation.
+    ///
+    /// All byte arrays are valid channel IDs.
+    #[inline]
+    pub fn from_raw_bytes(bytes: [u8; 32]) -> Self {
+        ChannelId(bytes)
+    }
+
+    /// Computes the identifier of a channel funded by the given outpoint (BOLT 2 v1 channels).
+    ///
+    /// The identifier is the funding transaction ID XOR-ed with big-endian output index.
+    /// The `txid` is expected in the wire (internal) byte order which is the **reverse** of what
+    /// block explorers and `bitcoind` display.
+    pub fn from_funding_outpoint(txid: [u8; 32], output_index: u16) -> Self {
+        let mut bytes = txid;
+        let index = output_index.to_be_bytes();
+        bytes[30] ^= index[0];
+        bytes[31] ^= index[1];
+        ChannelId(bytes)
+    }
+
+    /// Computes the identifier of a dual-funded channel (BOLT 2 v2 channels).
+    ///
+    /// The identifier is SHA256 of the lesser revocation basepoint concatenated with the greater
+    /// one where the points are compared as serialized compressed keys. Thus the order of
+    /// arguments doesn't matter.
+    ///
+    /// The basepoints can be passed as [`NodeId`] or (with `secp256k1` feature) as
+    /// [`NodePubkey`](crate::NodePubkey) since they use the same encoding.
+    pub fn from_revocation_basepoints<K: Into<NodeId>>(first: K, second: K) -> Self {
+        let first = first.into();
+        let second = second.into();
+        let (lesser, greater) = if first <= second { (first, second) } else { (second, first) };
+
+        let mut hasher = crate::hashes::Sha256::new();
+        hasher.update(lesser.as_ref());
+        hasher.update(greater.as_ref());
+        ChannelId(hasher.finalize())
+    }
+
+    /// Puts the byte representation into `Vec<u8>`.
+    ///
+    /// This is meant for convenience around APIs that require `Vec<u8>`. Since it allocates it's
+    /// best to avoid it if possible.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    /// Convenience conversion to byte array.
+    ///
+    /// This can be used instead of `From` to avoid inference issues.
+    pub fn to_array(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Internal monomorphic parsing method.
+    ///
+    /// This should improve codegen without requiring allocations.
+    fn parse_raw(s: &str) -> Result<Self, ParseErrorInner> {
+        fn decode_digit(digit: u8, pos: usize, s: &str) -> Result<u8, ParseErrorInner> {
+            match digit {
+                b'0'..=b'9' => Ok(digit - b'0'),
+                b'a'..=b'f' => Ok(digit - b'a' + 10),
+                b'A'..=b'F' => Ok(digit - b'A' + 10),
+                _ => Err(ParseErrorInner::Char { pos, c: s.chars().nth(pos).unwrap(), }),
+            }
+        }
+
+        let mut result = [0; 32];
+
+        if s.len() != 64 {
+            return Err(ParseErrorInner::Length)
+        }
+
+        for ((i, pair), dst) in s.as_bytes().chunks_exact(2).enumerate().zip(&mut result) {
+            *dst = decode_digit(pair[0], i * 2, s)? * 16 + decode_digit(pair[1], i * 2 + 1, s)?;
+        }
+
+        Ok(ChannelId(result))
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
+
+    /// Writes the fill character required number of times.
+    #[cfg(not(feature = "hex-conservative"))]
+    fn prefill(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use fmt::Write;
+
+        if let Some(width) = f.width() {
+            for _ in 0..width.saturating_sub(64) {
+                f.write_char(f.fill())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shows `ChannelId` as hex
+impl fmt::Display for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// Same as Display
+impl fmt::Debug for ChannelId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Same as Display
+impl fmt::LowerHex for ChannelId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "hex-conservative")]
+        {
+            hex_conservative::fmt_hex_exact!(f, 32, &self.0, hex_conservative::Case::Lower)
+        }
+
+        #[cfg(not(feature = "hex-conservative"))]
+        {
+            self.prefill(f)?;
+            for byte in &self.0 {
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// As `Display` but with upper-case letters
+impl fmt::UpperHex for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "hex-conservative")]
+        {
+            hex_conservative::fmt_hex_exact!(f, 32, &self.0, hex_conservative::Case::Upper)
+        }
+
+        #[cfg(not(feature = "hex-conservative"))]
+        {
+            self.prefill(f)?;
+            for byte in &self.0 {
+                write!(f, "{:02X}", byte)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Expects hex representation
+impl FromStr for ChannelId {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects hex representation
+impl<'a> TryFrom<&'a str> for ChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects hex representation
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for ChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects hex representation
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for ChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for ChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        slice.try_into()
+            .map(ChannelId)
+            .map_err(|_| DecodeError { len: slice.len() })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<Vec<u8>> for ChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+        (*vec).try_into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<[u8]>> for ChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: Box<[u8]>) -> Result<Self, Self::Error> {
+        (*slice).try_into()
+    }
+}
+
+impl From<[u8; 32]> for ChannelId {
+    fn from(value: [u8; 32]) -> Self {
+        ChannelId(value)
+    }
+}
+
+impl From<ChannelId> for [u8; 32] {
+    fn from(value: ChannelId) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<[u8; 32]> for ChannelId {
+    fn as_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for ChannelId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::borrow::Borrow<[u8; 32]> for ChannelId {
+    fn borrow(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl core::borrow::Borrow<[u8]> for ChannelId {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Error returned when decoding raw bytes fails
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    len: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid length {} bytes, the length must be 32 bytes", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The string that was attempted to be parsed
+    #[cfg(feature = "alloc")]
+    input: String,
+    /// Information about what exactly went wrong
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to parse{} channel ID", opt_fmt!("alloc", format_args!(" '{}' as", &self.input)); &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// Details about the error.
+///
+/// This is private to avoid committing to a representation.
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    /// Length != 64 chars
+    Length,
+    Char { pos: usize, c: char, },
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::Length => f.write_str("invalid length (must be 64 chars)"),
+            ParseErrorInner::Char { c, pos, } => write!(f, "invalid character '{}' at position {} (must be hex digit)", c, pos),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {}
+
+/// Implementation of `parse_arg::ParseArg` trait
+#[cfg(feature = "parse_arg")]
+mod parse_arg_impl {
+    use core::fmt;
+    use super::ChannelId;
+
+    impl parse_arg::ParseArgFromStr for ChannelId {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("a hex-encoded LN channel ID (64 hex digits/32 bytes)")
+        }
+    }
+}
+
+/// Implementations of `serde` traits
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use super::ChannelId;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
+    use core::convert::TryFrom;
+
+    /// Visitor for human-readable formats
+    struct HRVisitor;
+
+    impl<'de> Visitor<'de> for HRVisitor {
+        type Value = ChannelId;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a 64 digits long hex string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            use super::ParseErrorInner;
+
+            ChannelId::parse_raw(v).map_err(|error| {
+                match error {
+                    ParseErrorInner::Length => E::invalid_length(v.len(), &"64 hex digits"),
+                    ParseErrorInner::Char { c, pos: _, } => E::invalid_value(serde::de::Unexpected::Char(c), &"a hex digit"),
+                }
+            })
+        }
+    }
+
+    /// Visitor for non-human-readable (binary) formats
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = ChannelId;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("32 bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> where E: Error {
+            ChannelId::try_from(v).map_err(|error| E::invalid_length(error.len, &"32 bytes"))
+        }
+    }
+
+    /// `ChannelId` is serialized as hex to human-readable formats and as bytes to non-human-readable.
+    impl Serialize for ChannelId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+    }
+
+    /// `ChannelId` is deserialized as hex from human-readable formats and as bytes from non-human-readable.
+    impl<'de> Deserialize<'de> for ChannelId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(HRVisitor)
+            } else {
+                deserializer.deserialize_bytes(BytesVisitor)
+            }
+        }
+    }
+}
+
+/// Implementations of `postgres-types` traits
+#[cfg(feature = "postgres-types")]
+mod postgres_impl {
+    use alloc::boxed::Box;
+    use super::ChannelId;
+    use postgres_types::{ToSql, FromSql, IsNull, Type};
+    use bytes::BytesMut;
+    use std::error::Error;
+    use core::convert::TryInto;
+
+    /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+    ///
+    /// Stored as bytes if `BYTEA` is used, as hex string otherwise.
+    impl ToSql for ChannelId {
+        fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+            use core::fmt::Write;
+
+            match *ty {
+                Type::BYTEA => (&self.0 as &[_]).to_sql(ty, out),
+                _ => write!(out, "{}", self).map(|_| IsNull::No).map_err(|error| Box::new(error) as _)
+            }
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+    ///
+    /// Decoded as bytes if `BYTEA` is used, as hex string otherwise.
+    impl<'a> FromSql<'a> for ChannelId {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+            match *ty {
+                Type::BYTEA => <&[u8]>::from_sql(ty, raw)?.try_into().map_err(|error| Box::new(error) as _),
+                _ => <&str>::from_sql(ty, raw)?.parse().map_err(|error| Box::new(error) as _),
+            }
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
+        }
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::ChannelId;
+    use slog::{Key, Value, Record, Serializer};
+
+    /// Currently uses `Display` but may use `emit_bytes` if/when it's implemented.
+    impl Value for ChannelId {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    impl_error_value!(super::ParseError, super::DecodeError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelId;
+    use crate::NodeId;
+    use alloc::string::ToString;
+
+    #[test]
+    fn funding_outpoint_xor() {
+        let txid = [0xff; 32];
+        let channel_id = ChannelId::from_funding_outpoint(txid, 0x0102);
+        let mut expected = [0xff; 32];
+        expected[30] = 0xfe;
+        expected[31] = 0xfd;
+        assert_eq!(channel_id.to_array(), expected);
+        assert_eq!(ChannelId::from_funding_outpoint(txid, 0).to_array(), txid);
+    }
+
+    #[test]
+    fn revocation_basepoints() {
+        let a = "02eec7245d6b7d2ccb30380bfbe2a3648cd7a942653f5aa340edcea1f283686619".parse::<NodeId>().unwrap();
+        let b = "0324653eac434488002cc06bbfb7f10fe18991e35f9fe4302dbea6d2353dc0ab1c".parse::<NodeId>().unwrap();
+        let channel_id = ChannelId::from_revocation_basepoints(a, b);
+        assert_eq!(ChannelId::from_revocation_basepoints(b, a), channel_id);
+        assert_eq!(channel_id.to_string(), "906cf4dfd1bc5259d10bb36e170f0a92e0c49f12f3f9d7d729a0cae45d4b6768");
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let s = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+        let channel_id = s.parse::<ChannelId>().unwrap();
+        assert_eq!(channel_id.to_string(), s);
+        assert_eq!(s.to_uppercase().parse::<ChannelId>().unwrap(), channel_id);
+        assert_eq!(alloc::format!("{:X}", channel_id), s.to_uppercase());
+    }
+
+    chk_err_impl! {
+        parse_channel_id_error_empty, "", ChannelId, [
+            "failed to parse '' as channel ID",
+            "invalid length (must be 64 chars)",
+        ], [
+            "failed to parse channel ID",
+            "invalid length (must be 64 chars)",
+        ];
+        parse_channel_id_error_char, "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1g", ChannelId, [
+            "failed to parse '000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1g' as channel ID",
+            "invalid character 'g' at position 63 (must be hex digit)",
+        ], [
+            "failed to parse channel ID",
+            "invalid character 'g' at position 63 (must be hex digit)",
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let s = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+        let channel_id = s.parse::<ChannelId>().unwrap();
+        let json = serde_json::to_string(&channel_id).unwrap();
+        assert_eq!(json, alloc::format!("\"{}\"", s));
+        assert_eq!(serde_json::from_str::<ChannelId>(&json).unwrap(), channel_id);
+        let bytes = bincode::serialize(&channel_id).unwrap();
+        assert_eq!(bincode::deserialize::<ChannelId>(&bytes).unwrap(), channel_id);
+    }
+}
@@ -0,0 +1,611 @@
+//! Funding outpoint of a channel
+//!
+//! This module provides the [`ChannelPoint`] type and the related error types.
+
+use core::convert::{TryFrom, TryInto};
+use core::str::FromStr;
+use core::fmt;
+use crate::ChannelId;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Reference to the funding output of a channel - transaction ID and output index.
+///
+/// This is what LND calls `channel_point` and what is represented as `OutPoint` in Bitcoin.
+///
+/// ## Byte order
+///
+/// Transaction IDs are hashes that are traditionally displayed in the **reverse** order of bytes
+/// compared to how they are stored in transactions and sent over the wire. Unfortunately, APIs
+/// are not consistent about which order they use (e.g. LND returns the internal order in its
+/// `funding_txid_bytes` field but the display order in `funding_txid_str`) so this type doesn't
+/// provide a plain `from_txid` constructor. Instead the byte order is always explicit in the
+/// name of the method.
+///
+/// The text representation is `TXID:OUTPUT_INDEX` with `TXID` in display order, same as in
+/// `bitcoind`, block explorers and LND.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::ChannelPoint;
+///
+/// let channel_point = "b2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201:1".parse::<ChannelPoint>().unwrap();
+/// assert_eq!(channel_point.output_index(), 1);
+/// assert_eq!(channel_point.txid_display_order()[0], 0xb2);
+/// assert_eq!(channel_point.txid_internal_order()[0], 0x01);
+/// assert_eq!(channel_point.channel_id().unwrap().to_string(), "01d2c1f3a3b8e0e0574d6a4ad9c8f0a7f6e1a1a5f0b4d6b7f9b48a8a9acde7b3");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ChannelPoint {
+    /// Stored in the internal (wire) order
+    txid: [u8; 32],
+    output_index: u32,
+}
+
+impl ChannelPoint {
+    /// Creates `ChannelPoint` from transaction ID in the internal (wire) byte order.
+    ///
+    /// This is the order used in transactions, P2P messages and `bitcoin::Txid::to_byte_array`.
+    #[inline]
+    pub fn from_txid_internal_order(txid: [u8; 32], output_index: u32) -> Self {
+        ChannelPoint {
+            txid,
+            output_index,
+        }
+    }
+
+    /// Creates `ChannelPoint` from transaction ID in the display (reversed) byte order.
+    ///
+    /// This is the order used when transaction IDs are shown as hex, e.g. by `bitcoind` or block
+    /// explorers.
+    #[inline]
+    pub fn from_txid_display_order(mut txid: [u8; 32], output_index: u32) -> Self {
+        txid.reverse();
+        ChannelPoint {
+            txid,
+            output_index,
+        }
+    }
+
+    /// Returns transaction ID in the internal (wire) byte order.
+    #[inline]
+    pub fn txid_internal_order(&self) -> [u8; 32] {
+        self.txid
+    }
+
+    /// Returns transaction ID in the display (reversed) byte order.
+    #[inline]
+    pub fn txid_display_order(&self) -> [u8; 32] {
+        let mut txid = self.txid;
+        txid.reverse();
+        txid
+    }
+
+    /// Returns the index of the funding output.
+    #[inline]
+    pub fn output_index(&self) -> u32 {
+        self.output_index
+    }
+
+    /// Computes the [`ChannelId`] of a channel funded by this outpoint.
+    ///
+    /// BOLT 2 limits the funding output index to 16 bits so this returns `None` if the index is
+    /// larger. Note that dual-funded channels derive their ID differently, see
+    /// [`ChannelId::from_revocation_basepoints`].
+    pub fn channel_id(&self) -> Option<ChannelId> {
+        let output_index = self.output_index.try_into().ok()?;
+        Some(ChannelId::from_funding_outpoint(self.txid, output_index))
+    }
+
+    /// Returns the consensus encoding - transaction ID in the internal order followed by
+    /// little-endian output index.
+    pub fn to_bytes(&self) -> [u8; 36] {
+        let mut bytes = [0; 36];
+        bytes[..32].copy_from_slice(&self.txid);
+        bytes[32..].copy_from_slice(&self.output_index.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes the consensus encoding.
+    pub fn from_bytes(bytes: [u8; 36]) -> Self {
+        let mut txid = [0; 32];
+        txid.copy_from_slice(&bytes[..32]);
+        let output_index = u32::from_le_bytes([bytes[32], bytes[33], bytes[34], bytes[35]]);
+        ChannelPoint {
+            txid,
+            output_index,
+        }
+    }
+
+    /// Internal monomorphic parsing method.
+    ///
+    /// This should improve codegen without requiring allocations.
+    fn parse_raw(s: &str) -> Result<Self, ParseErrorInner> {
+        fn decode_digit(digit: u8, pos: usize, s: &str) -> Result<u8, ParseErrorInner> {
+            match digit {
+                b'0'..=b'9' => Ok(digit - b'0'),
+                b'a'..=b'f' => Ok(digit - b'a' + 10),
+                b'A'..=b'F' => Ok(digit - b'A' + 10),
+                _ => Err(ParseErrorInner::Char { pos, c: s.chars().nth(pos).unwrap(), }),
+            }
+        }
+
+        let colon = s.rfind(':').ok_or(ParseErrorInner::MissingSeparator)?;
+        let (txid_str, output_index) = (&s[..colon], &s[(colon + 1)..]);
+
+        if txid_str.len() != 64 {
+            return Err(ParseErrorInner::TxidLength);
+        }
+
+        let mut txid = [0; 32];
+        // display order - the last pair of digits is the first byte
+        for ((i, pair), dst) in txid_str.as_bytes().chunks_exact(2).enumerate().zip(txid.iter_mut().rev()) {
+            *dst = decode_digit(pair[0], i * 2, s)? * 16 + decode_digit(pair[1], i * 2 + 1, s)?;
+        }
+
+        let output_index = output_index.parse().map_err(ParseErrorInner::OutputIndex)?;
+
+        Ok(ChannelPoint {
+            txid,
+            output_index,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
+}
+
+/// Shows `ChannelPoint` as `TXID:OUTPUT_INDEX` with transaction ID in display order
+impl fmt::Display for ChannelPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.txid.iter().rev() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ":{}", self.output_index)
+    }
+}
+
+/// Same as Display
+impl fmt::Debug for ChannelPoint {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Expects `TXID:OUTPUT_INDEX` with transaction ID in display order
+impl FromStr for ChannelPoint {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `TXID:OUTPUT_INDEX` with transaction ID in display order
+impl<'a> TryFrom<&'a str> for ChannelPoint {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `TXID:OUTPUT_INDEX` with transaction ID in display order
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for ChannelPoint {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `TXID:OUTPUT_INDEX` with transaction ID in display order
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for ChannelPoint {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects consensus encoding (36 bytes)
+impl<'a> TryFrom<&'a [u8]> for ChannelPoint {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        slice.try_into()
+            .map(Self::from_bytes)
+            .map_err(|_| DecodeError { len: slice.len() })
+    }
+}
+
+/// Expects consensus encoding (36 bytes)
+#[cfg(feature = "alloc")]
+impl TryFrom<Vec<u8>> for ChannelPoint {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+        (*vec).try_into()
+    }
+}
+
+/// Expects consensus encoding (36 bytes)
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<[u8]>> for ChannelPoint {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: Box<[u8]>) -> Result<Self, Self::Error> {
+        (*slice).try_into()
+    }
+}
+
+/// Error returned when decoding raw bytes fails
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    len: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid length {} bytes, the length must be 36 bytes", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The string that was attempted to be parsed
+    #[cfg(feature = "alloc")]
+    input: String,
+    /// Information about what exactly went wrong
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to parse{} channel point", opt_fmt!("alloc", format_args!(" '{}' as", &self.input)); &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// Details about the error.
+///
+/// This is private to avoid committing to a representation.
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    MissingSeparator,
+    /// Length != 64 chars
+    TxidLength,
+    Char { pos: usize, c: char, },
+    OutputIndex(core::num::ParseIntError),
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::MissingSeparator => f.write_str("missing ':' separating transaction ID and output index"),
+            ParseErrorInner::TxidLength => f.write_str("invalid length of transaction ID (must be 64 chars)"),
+            ParseErrorInner::Char { c, pos, } => write!(f, "invalid character '{}' at position {} (must be hex digit)", c, pos),
+            ParseErrorInner::OutputIndex(error) => write_err!(f, "invalid output index"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseErrorInner::MissingSeparator | ParseErrorInner::TxidLength | ParseErrorInner::Char { .. } => None,
+            ParseErrorInner::OutputIndex(error) => Some(error),
+        }
+    }
+}
+
+/// Implementation of `parse_arg::ParseArg` trait
+#[cfg(feature = "parse_arg")]
+mod parse_arg_impl {
+    use core::fmt;
+    use super::ChannelPoint;
+
+    impl parse_arg::ParseArgFromStr for ChannelPoint {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("a channel point in the form TXID:OUTPUT_INDEX")
+        }
+    }
+}
+
+/// Conversions from/to `bitcoin::OutPoint`
+#[cfg(feature = "bitcoin")]
+mod bitcoin_impl {
+    use super::ChannelPoint;
+    use bitcoin::hashes::Hash;
+
+    impl From<bitcoin::OutPoint> for ChannelPoint {
+        fn from(value: bitcoin::OutPoint) -> Self {
+            ChannelPoint::from_txid_internal_order(value.txid.to_byte_array(), value.vout)
+        }
+    }
+
+    impl From<ChannelPoint> for bitcoin::OutPoint {
+        fn from(value: ChannelPoint) -> Self {
+            bitcoin::OutPoint {
+                txid: bitcoin::Txid::from_byte_array(value.txid),
+                vout: value.output_index,
+            }
+        }
+    }
+}
+
+/// Implementations of `serde` traits
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use super::ChannelPoint;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
+    use core::convert::TryFrom;
+
+    /// Visitor for human-readable formats
+    struct HRVisitor;
+
+    impl<'de> Visitor<'de> for HRVisitor {
+        type Value = ChannelPoint;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a channel point in the form TXID:OUTPUT_INDEX")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            ChannelPoint::parse_raw(v).map_err(|error| E::custom(format_args!("invalid channel point '{}': {}", v, error)))
+        }
+    }
+
+    /// Visitor for non-human-readable (binary) formats
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = ChannelPoint;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("36 bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> where E: Error {
+            ChannelPoint::try_from(v).map_err(|error| E::invalid_length(error.len, &"36 bytes"))
+        }
+    }
+
+    /// `ChannelPoint` is serialized as `TXID:OUTPUT_INDEX` to human-readable formats and as
+    /// consensus-encoded bytes to non-human-readable.
+    impl Serialize for ChannelPoint {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                serializer.serialize_bytes(&self.to_bytes())
+            }
+        }
+    }
+
+    /// `ChannelPoint` is deserialized from `TXID:OUTPUT_INDEX` from human-readable formats and
+    /// from consensus-encoded bytes from non-human-readable.
+    impl<'de> Deserialize<'de> for ChannelPoint {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(HRVisitor)
+            } else {
+                deserializer.deserialize_bytes(BytesVisitor)
+            }
+        }
+    }
+}
+
+/// Implementations of `postgres-types` traits
+#[cfg(feature = "postgres-types")]
+mod postgres_impl {
+    use alloc::boxed::Box;
+    use super::ChannelPoint;
+    use postgres_types::{ToSql, FromSql, IsNull, Type};
+    use bytes::BytesMut;
+    use std::error::Error;
+    use core::convert::TryInto;
+
+    /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+    ///
+    /// Stored as consensus-encoded bytes if `BYTEA` is used, as `TXID:OUTPUT_INDEX` string
+    /// otherwise.
+    impl ToSql for ChannelPoint {
+        fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+            use core::fmt::Write;
+
+            match *ty {
+                Type::BYTEA => (&self.to_bytes() as &[_]).to_sql(ty, out),
+                _ => write!(out, "{}", self).map(|_| IsNull::No).map_err(|error| Box::new(error) as _)
+            }
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+    ///
+    /// Decoded as consensus-encoded bytes if `BYTEA` is used, as `TXID:OUTPUT_INDEX` string
+    /// otherwise.
+    impl<'a> FromSql<'a> for ChannelPoint {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+            match *ty {
+                Type::BYTEA => <&[u8]>::from_sql(ty, raw)?.try_into().map_err(|error| Box::new(error) as _),
+                _ => <&str>::from_sql(ty, raw)?.parse().map_err(|error| Box::new(error) as _),
+            }
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
+        }
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::ChannelPoint;
+    use slog::{Key, Value, Record, Serializer};
+
+    /// Uses `Display`
+    impl Value for ChannelPoint {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    impl_error_value!(super::ParseError, super::DecodeError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelPoint;
+    use alloc::string::ToString;
+
+    const TXID: &str = "b2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201";
+
+    #[test]
+    fn byte_orders() {
+        let mut display = [0; 32];
+        for (i, byte) in display.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut internal = display;
+        internal.reverse();
+
+        let a = ChannelPoint::from_txid_display_order(display, 7);
+        let b = ChannelPoint::from_txid_internal_order(internal, 7);
+        assert_eq!(a, b);
+        assert_eq!(a.txid_display_order(), display);
+        assert_eq!(a.txid_internal_order(), internal);
+        assert_eq!(a.to_string(), "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f:7");
+    }
+
+    #[test]
+    fn parse_display_roundtrip() {
+        let s = alloc::format!("{}:4294967295", TXID);
+        let channel_point = s.parse::<ChannelPoint>().unwrap();
+        assert_eq!(channel_point.output_index(), u32::MAX);
+        assert_eq!(channel_point.to_string(), s);
+        assert!(channel_point.channel_id().is_none());
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let channel_point = alloc::format!("{}:258", TXID).parse::<ChannelPoint>().unwrap();
+        let bytes = channel_point.to_bytes();
+        assert_eq!(bytes[..32], channel_point.txid_internal_order());
+        assert_eq!(bytes[32..], [2, 1, 0, 0]);
+        assert_eq!(ChannelPoint::from_bytes(bytes), channel_point);
+    }
+
+    #[test]
+    fn channel_id() {
+        let channel_point = alloc::format!("{}:258", TXID).parse::<ChannelPoint>().unwrap();
+        let channel_id = channel_point.channel_id().unwrap();
+        assert_eq!(channel_id.to_string(), "01d2c1f3a3b8e0e0574d6a4ad9c8f0a7f6e1a1a5f0b4d6b7f9b48a8a9acde6b0");
+    }
+
+    chk_err_impl! {
+        parse_channel_point_error_empty, "", ChannelPoint, [
+            "failed to parse '' as channel point",
+            "missing ':' separating transaction ID and output index",
+        ], [
+            "failed to parse channel point",
+            "missing ':' separating transaction ID and output index",
+        ];
+        parse_channel_point_error_txid_length, "abcd:1", ChannelPoint, [
+            "failed to parse 'abcd:1' as channel point",
+            "invalid length of transaction ID (must be 64 chars)",
+        ], [
+            "failed to parse channel point",
+            "invalid length of transaction ID (must be 64 chars)",
+        ];
+        parse_channel_point_error_char, "x2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201:1", ChannelPoint, [
+            "failed to parse 'x2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201:1' as channel point",
+            "invalid character 'x' at position 0 (must be hex digit)",
+        ], [
+            "failed to parse channel point",
+            "invalid character 'x' at position 0 (must be hex digit)",
+        ];
+        parse_channel_point_error_output_index, "b2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201:-1", ChannelPoint, [
+            "failed to parse 'b2e7cd9a8a8ab4f9b7d6b4f0a5a1e1f6a7f0c8d94a6a4d57e0e0b8a3f3c1d201:-1' as channel point",
+            "invalid output index",
+            "invalid digit found in string",
+        ], [
+            "failed to parse channel point",
+            "invalid output index",
+            "invalid digit found in string",
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let s = alloc::format!("{}:1", TXID);
+        let channel_point = s.parse::<ChannelPoint>().unwrap();
+        let json = serde_json::to_string(&channel_point).unwrap();
+        assert_eq!(json, alloc::format!("\"{}\"", s));
+        assert_eq!(serde_json::from_str::<ChannelPoint>(&json).unwrap(), channel_point);
+        let bytes = bincode::serialize(&channel_point).unwrap();
+        assert_eq!(bincode::deserialize::<ChannelPoint>(&bytes).unwrap(), channel_point);
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin")]
+    fn bitcoin_outpoint() {
+        let s = alloc::format!("{}:1", TXID);
+        let out_point = s.parse::<bitcoin::OutPoint>().unwrap();
+        let channel_point = ChannelPoint::from(out_point);
+        assert_eq!(channel_point.to_string(), s);
+        assert_eq!(bitcoin::OutPoint::from(channel_point), out_point);
+    }
+}
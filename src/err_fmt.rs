@@ -1,4 +1,4 @@
-/// Macros helping with implementations of error formatting.
+//! Macros helping with implementations of error formatting.
 
 #[cfg(any(not(feature = "alloc"), feature = "std"))]
 use core::fmt;
@@ -64,7 +64,7 @@ macro_rules! opt_fmt {
 
 /// Formats error optionally with error source appended (delimited by `: `) *if `$feature` is OFF*
 macro_rules! write_err_ext {
-    ($feature:literal, $writer:expr, $string:literal $(, $args:expr),*; $source:expr) => {
+    ($feature:literal, $writer:expr, $string:literal $(, $args:expr)*; $source:expr) => {
         {
             let _ = &$source;
             write!($writer, concat!($string, "{}") $(, $args)*, opt_fmt!(not($feature), format_args!(": {}", $source)))
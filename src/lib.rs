@@ -14,10 +14,18 @@
 //! The most important types currently available:
 //!
 //! * [`Amount`] - similar to [`bitcoin_units::Amount`] but with millisatoshi precision
+//! * [`RoutingFees`] - fee policy of a channel computing fees according to BOLT 7
 //! * [`P2PAddress`] - address of a node usually represented in text as `node_id_hex@host:port`
 //! * [`NodeId`] - the byte representation of node's public key (no crypto operations)
 //! * [`NodePubkey`] - newtype around [`secp256k1::PublicKey`] to distinguish node public key from
-//!                    other keys. Requires `secp256k1` feature.
+//!   other keys. Requires `secp256k1` feature.
+//! * [`ChannelId`] - 32-byte channel identifier derived from the funding outpoint or revocation
+//!   basepoints
+//! * [`ChannelPoint`] - funding outpoint of a channel represented in text as `txid:output_index`
+//! * [`PaymentHash`], [`PaymentPreimage`], [`PaymentSecret`] - 32-byte payment values that are
+//!   easy to mix up, with preimage verification
+//! * [`ShortChannelId`] - compact identifier of a channel usually represented as `BLOCKxTXxOUTPUT`
+//! * [`wire::BigSize`] and [`wire::TruncatedInt`] - BOLT 1 integer encodings used in TLV records
 //!
 //! Note: invoice is not here and isn't planned because it already exists in a separate crate.
 //!
@@ -30,32 +38,35 @@
 //! Ativate using features of the same name.
 //!
 //! * `std` - enables [`P2PAddress`] and implements [`std::error::Error`] for error types.
-//!           Enabled by default, implies `alloc.
+//!   Enabled by default, implies `alloc.
 //! * `alloc` - enables conversions from/to heap-allocated types as well as additional error
-//!             information.
+//!   information.
 //! * [`bitcoin-units`] - converting between types
+//! * [`bitcoin`] - converting [`ChannelPoint`] from/to `bitcoin::OutPoint`
 //! * [`serde`] - serialization and deserialization of types
 //! * [`postgres-types`](postgres_types) - storing and retrieving from SQL
 //! * [`parse_arg`] - parsing arguments into types in this crate
 //! * [`secp256k1`] - provides `NodePubkey`
 //! * [`slog`] - provides `slog::Value` and (where relevant) `slog::KV` implementations for the types
+//! * [`tokio`] - asynchronous resolution of [`P2PAddress`] and connecting to it. Implies `std`.
+//! * [`idna`] - parsing internationalized hostnames in [`P2PAddress`]. Implies `std`.
 //!
 //! **Important: some combinations may benefit from additional features!**
 //! See below.
 //!
 //! ### Additional features
 //!
-//! * `hex-conservative` - improves the performance of displaying `NodeId`/`NodePubkey` at the cost
-//!                        of additional dependency
+//! * `hex-conservative` - improves the performance of displaying `NodeId`/`NodePubkey`, `ChannelId`
+//!   and payment types at the cost of additional dependency
 //! * `node_pubkey_verify` - convenience function for verifying messages signed with
-//!                          [`NodePubkey`], implies `secp256k1/bitcoin_hashes`
+//!   [`NodePubkey`], implies `secp256k1/bitcoin_hashes`
 //! * `node_pubkey_recovery` - convenience function for verifying lightning messages
-//!                            signed with [`NodePubkey`], implies `node_pubkey_verify` and
-//!                            `secp256k1/recovery`
+//!   signed with [`NodePubkey`], implies `node_pubkey_verify` and
+//!   `secp256k1/recovery`
 //! * `secp256k1_std` - required for [`node_pubkey::ParseError`] to return `secp256k1::Error` from
-//!                     `source()` method of [`std::error::Error`]
+//!   `source()` method of [`std::error::Error`]
 //! * `serde_alloc` - required for specialization of `serde::de::Visitor::visit_string` method to
-//!                   avoid allocation
+//!   avoid allocation
 //! * `slog_std` - required for error types to use [`slog::Serializer::emit_error`] for logging
 //!
 //! Feel free to contribute your own!
@@ -112,6 +123,9 @@ pub extern crate alloc;
 #[cfg(feature = "bitcoin-units")]
 pub extern crate bitcoin_units;
 
+#[cfg(feature = "bitcoin")]
+pub extern crate bitcoin;
+
 #[cfg(feature = "parse_arg")]
 pub extern crate parse_arg;
 
@@ -127,15 +141,28 @@ pub extern crate serde;
 #[cfg(feature = "slog")]
 pub extern crate slog;
 
+#[cfg(feature = "tokio")]
+pub extern crate tokio_real as tokio;
+
+#[cfg(feature = "idna")]
+pub extern crate idna_real as idna;
+
 #[macro_use]
 mod macros;
 #[macro_use]
 pub(crate) mod err_fmt;
+mod hashes;
 
 pub mod node_id;
 #[cfg(any(feature = "std", rust_v_1_77))]
 pub mod p2p_address;
 pub mod amount;
+pub mod routing_fees;
+pub mod short_channel_id;
+pub mod channel_id;
+pub mod channel_point;
+pub mod payment;
+pub mod wire;
 #[cfg(feature = "secp256k1")]
 pub mod node_pubkey;
 
@@ -143,5 +170,10 @@ pub use node_id::NodeId;
 #[cfg(any(feature = "std", rust_v_1_77))]
 pub use p2p_address::P2PAddress;
 pub use amount::Amount;
+pub use routing_fees::RoutingFees;
+pub use short_channel_id::ShortChannelId;
+pub use channel_id::ChannelId;
+pub use channel_point::ChannelPoint;
+pub use payment::{PaymentHash, PaymentPreimage, PaymentSecret};
 #[cfg(feature = "secp256k1")]
 pub use node_pubkey::NodePubkey;
@@ -512,12 +512,7 @@ mod postgres_impl {
         }
 
         fn accepts(ty: &Type) -> bool {
-            match *ty {
-                Type::BYTEA => true,
-                Type::TEXT => true,
-                Type::VARCHAR => true,
-                _ => false,
-            }
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
         }
 
         postgres_types::to_sql_checked!();
@@ -535,12 +530,7 @@ mod postgres_impl {
         }
 
         fn accepts(ty: &Type) -> bool {
-            match *ty {
-                Type::BYTEA => true,
-                Type::TEXT => true,
-                Type::VARCHAR => true,
-                _ => false,
-            }
+            matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
         }
     }
 }
@@ -21,7 +21,7 @@ use alloc::{boxed::Box, string::String, vec::Vec};
 /// * `NodeId` is more performant for non-cryptographic operations.
 /// * `NodeId` can not perform any cryptographic operations itself.
 /// * `NodePubkey`, despite its field being public, maintains more invariants.
-///    *In this library*, a valid `NodeId` is **not** guaranteed to be a valid `NodePubkey`
+///   *In this library*, a valid `NodeId` is **not** guaranteed to be a valid `NodePubkey`
 ///
 /// ## Example
 ///
@@ -394,25 +394,32 @@ mod parse_arg_impl {
     }
 }
 
-#[cfg(all(feature = "serde", feature = "secp256k1/serde"))]
-mod serde_impls {
-    use serde::{Serialize, Deserialize, Serializer, Deserializer};
+/// Implementations of `serde` traits
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::Error};
     use super::NodePubkey;
-    use secp256k1::PublicKey;
+    use crate::NodeId;
+    use core::convert::TryFrom;
 
-    /// `NodePubkey` is transparently serialized `secp256k1::PublicKey`
-    #[cfg_attr(all(feature = "serde", feature = "secp256k1/serde"))]
+    /// `NodePubkey` is serialized the same way as `NodeId`: as hex to human-readable formats and
+    /// as 33 bytes to non-human-readable.
     impl Serialize for NodePubkey {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-            Seialize::serialize(&self.0, serializer)
+            self.to_node_id().serialize(serializer)
         }
     }
 
-    /// `NodePubkey` is transparently deserialized `secp256k1::PublicKey`
-    #[cfg_attr(all(feature = "serde", feature = "secp256k1/serde"))]
+    /// `NodePubkey` is deserialized the same way as `NodeId`: as hex from human-readable formats
+    /// and as 33 bytes from non-human-readable.
+    ///
+    /// The deserialized key is validated.
     impl<'de> Deserialize<'de> for NodePubkey {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-            Ok(PublicKey::deserialize(deserializer)?)
+            let node_id = NodeId::deserialize(deserializer)?;
+            NodePubkey::try_from(node_id).map_err(|_| {
+                D::Error::invalid_value(serde::de::Unexpected::Bytes(AsRef::<[u8]>::as_ref(&node_id)), &"a valid secp256k1 public key")
+            })
         }
     }
 }
@@ -522,4 +529,33 @@ mod tests {
             "irrelevant, we definitely have std and thus alloc",
         ];
     }
+
+    #[cfg(feature = "serde")]
+    const MARVIN: &str = "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482";
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_human_readable_roundtrip() {
+        let pubkey = MARVIN.parse::<NodePubkey>().unwrap();
+        let json = serde_json::to_string(&pubkey).unwrap();
+        assert_eq!(json, alloc::format!("\"{}\"", MARVIN));
+        assert_eq!(serde_json::from_str::<NodePubkey>(&json).unwrap(), pubkey);
+        assert!(serde_json::from_str::<NodePubkey>("\"020000000000000000000000000000000000000000000000000000000000000000\"").is_err());
+        assert!(serde_json::from_str::<NodePubkey>("\"029ef8\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_roundtrip() {
+        let pubkey = MARVIN.parse::<NodePubkey>().unwrap();
+        let bytes = bincode::serialize(&pubkey).unwrap();
+        // u64 length prefix followed by raw bytes
+        assert_eq!(bytes.len(), 8 + 33);
+        assert_eq!(bytes[8..], pubkey.to_node_id().to_array());
+        assert_eq!(bincode::deserialize::<NodePubkey>(&bytes).unwrap(), pubkey);
+
+        let mut invalid = bytes.clone();
+        invalid[9..].iter_mut().for_each(|byte| *byte = 0);
+        assert!(bincode::deserialize::<NodePubkey>(&invalid).is_err());
+    }
 }
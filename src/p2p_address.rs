@@ -8,8 +8,8 @@ use core::str::FromStr;
 use core::fmt;
 #[cfg(feature = "std")]
 use std::io;
-#[cfg(feature = "std")]
-use std::vec::Vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use crate::NodeId;
 
 #[cfg(rust_v_1_77)]
@@ -20,80 +20,159 @@ use std::net;
 #[cfg(feature = "alloc")]
 use alloc::{boxed::Box, string::String, borrow::ToOwned, string::ToString};
 
+pub mod descriptor;
+pub mod reachability;
+#[cfg(feature = "std")]
+pub mod socks5;
+pub mod uri;
+
+pub use reachability::{NetworkKind, ReachabilityPolicy};
+
 const LN_DEFAULT_PORT: u16 = 9735;
 
-/// Abstracts over string operations.
+/// Version byte of onion v3 addresses
+const ONION_V3_VERSION: u8 = 3;
+/// Length of base32-encoded onion v3 address without the `.onion` suffix
+const ONION_V3_LEN: usize = 56;
+/// Length of base32-encoded onion v2 address without the `.onion` suffix
+const ONION_V2_LEN: usize = 16;
+const ONION_SUFFIX: &str = ".onion";
+/// RFC 4648 base32 alphabet as used by Tor
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+/// Maximum length of hostname - the limit of BOLT 7 DNS address descriptor
+const MAX_HOSTNAME_LEN: usize = 255;
+/// Maximum length of a single label in hostname as defined by RFC 1035
+const MAX_LABEL_LEN: usize = 63;
+
+/// Abstracts over string types accepted by parsing functions.
 ///
-/// This trait enables efficient conversions.
+/// This trait enables storing the input in errors without needless allocations.
 #[cfg(feature = "alloc")]
-trait StringOps: AsRef<str> + Into<String> {
-    /// Converts given range of `self` into `String`
-    fn into_substring(self, start: usize, end: usize) -> String;
-}
+trait StringOps: AsRef<str> + Into<String> { }
 
 #[cfg(not(feature = "alloc"))]
 trait StringOps: AsRef<str> { }
 
-/// The implementation avoids allocations - whole point of the trait.
 #[cfg(feature = "alloc")]
-impl StringOps for String {
-    fn into_substring(mut self, start: usize, end: usize) -> String {
-        self.replace_range(0..start, "");
-        self.truncate(end - start);
-        self
-    }
-}
+impl StringOps for String { }
 
-impl<'a> StringOps for &'a str {
-    #[cfg(feature = "alloc")]
-    fn into_substring(self, start: usize, end: usize) -> String {
-        self[start..end].to_owned()
-    }
-}
+impl StringOps for &str { }
 
-/// Avoids allocations but has to store capacity
 #[cfg(feature = "alloc")]
-impl StringOps for Box<str> {
-    fn into_substring(self, start: usize, end: usize) -> String {
-        String::from(self).into_substring(start, end)
-    }
-}
+impl StringOps for Box<str> { }
 
-/// Internal type that can store IP addresses without allocations.
+/// Internal type that can store all kinds of hosts without allocations.
 ///
 /// This may be (partially) public in the future.
+// Boxing the hostname would defeat the purpose of storing it inline
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 enum HostInner {
     Ip(net::IpAddr),
-    #[cfg(feature = "alloc")]
-    Hostname(String),
-    // TODO: onion
+    /// IPv6 address with non-zero scope ID (zone index)
+    Ipv6Scoped(net::Ipv6Addr, u32),
+    Hostname(Hostname),
+    /// ed25519 public key of onion v3 service
+    OnionV3([u8; 32]),
+}
+
+/// Validated hostname stored inline.
+///
+/// BOLT 7 limits hostnames to 255 bytes so we can store them in a fixed-size buffer and avoid
+/// allocations.
+#[derive(Clone)]
+struct Hostname {
+    len: u8,
+    bytes: [u8; MAX_HOSTNAME_LEN],
+}
+
+impl Hostname {
+    /// Copies already validated hostname into the buffer.
+    ///
+    /// Panics if the hostname is longer than 255 bytes.
+    fn from_validated(hostname: &str) -> Self {
+        let mut bytes = [0; MAX_HOSTNAME_LEN];
+        bytes[..hostname.len()].copy_from_slice(hostname.as_bytes());
+        Hostname {
+            len: hostname.len() as u8,
+            bytes,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // We only ever store valid hostnames which are ASCII
+        core::str::from_utf8(&self.bytes[..usize::from(self.len)]).expect("hostname is ASCII")
+    }
 }
 
 /// Type representing network address of an LN node.
 ///
-/// This type can avoid allocations if the value is an IP address.
+/// This type never allocates - hostnames are stored inline since their length is limited to 255
+/// bytes. Onion addresses are validated when parsing - only v3 addresses with a correct checksum
+/// are accepted.
 ///
 /// **Important: consumer code MUST NOT match on this using `Host { .. }` syntax.
 #[derive(Clone)]
 pub struct Host(HostInner);
 
 impl Host {
+    /// Creates onion v3 host from the ed25519 public key of the service.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::p2p_address::Host;
+    ///
+    /// let host = Host::from_onion_v3_pubkey([42; 32]);
+    /// assert!(host.is_onion());
+    /// assert_eq!(host.onion_v3_pubkey(), Some(&[42; 32]));
+    /// ```
+    pub fn from_onion_v3_pubkey(pubkey: [u8; 32]) -> Self {
+        Host(HostInner::OnionV3(pubkey))
+    }
+
     /// Returns true if it's an onion (Tor) adress.
     pub fn is_onion(&self) -> bool {
         match &self.0 {
-            #[cfg(feature = "alloc")]
-            HostInner::Hostname(hostname) => hostname.ends_with(".onion"),
-            HostInner::Ip(_) => false,
+            HostInner::Hostname(_) => false,
+            HostInner::Ip(_) | HostInner::Ipv6Scoped(_, _) => false,
+            HostInner::OnionV3(_) => true,
         }
     }
 
     /// Returns true if it's an IP adress.
+    ///
+    /// See also [`network_kind`](Self::network_kind) for more detailed classification.
     pub fn is_ip_addr(&self) -> bool {
         match &self.0 {
-            #[cfg(feature = "alloc")]
             HostInner::Hostname(_) => false,
-            HostInner::Ip(_) => true,
+            HostInner::Ip(_) | HostInner::Ipv6Scoped(_, _) => true,
+            HostInner::OnionV3(_) => false,
+        }
+    }
+
+    /// Returns the scope ID (zone index) if this is a scoped IPv6 address such as `fe80::1%2`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::p2p_address::{Host, HostPort};
+    ///
+    /// let host_port = "[fe80::1%2]:9735".parse::<HostPort<Host>>().unwrap();
+    /// assert_eq!(host_port.0.ipv6_scope_id(), Some(2));
+    /// ```
+    pub fn ipv6_scope_id(&self) -> Option<u32> {
+        match &self.0 {
+            HostInner::Ipv6Scoped(_, scope_id) => Some(*scope_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the ed25519 public key of the onion service if this is an onion v3 address.
+    pub fn onion_v3_pubkey(&self) -> Option<&[u8; 32]> {
+        match &self.0 {
+            HostInner::OnionV3(pubkey) => Some(pubkey),
+            _ => None,
         }
     }
 }
@@ -102,16 +181,199 @@ impl fmt::Display for Host {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
             HostInner::Ip(addr) => fmt::Display::fmt(&addr, f),
-            #[cfg(feature = "alloc")]
-            HostInner::Hostname(addr) => fmt::Display::fmt(&addr, f),
+            HostInner::Ipv6Scoped(addr, scope_id) => write!(f, "{}%{}", addr, scope_id),
+            HostInner::Hostname(addr) => f.pad(addr.as_str()),
+            HostInner::OnionV3(pubkey) => {
+                let mut buf = [0u8; ONION_V3_LEN + ONION_SUFFIX.len()];
+                encode_onion_v3(pubkey, &mut buf);
+                // base32 alphabet and the suffix are ASCII
+                f.pad(core::str::from_utf8(&buf).expect("ASCII is valid UTF-8"))
+            },
+        }
+    }
+}
+
+/// Same as Display
+impl fmt::Debug for Host {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Computes the checksum of onion v3 address as defined in Tor `rend-spec-v3`.
+fn onion_v3_checksum(pubkey: &[u8; 32]) -> [u8; 2] {
+    let mut hasher = crate::hashes::Sha3_256::new();
+    hasher.update(b".onion checksum");
+    hasher.update(pubkey);
+    hasher.update(&[ONION_V3_VERSION]);
+    let hash = hasher.finalize();
+    [hash[0], hash[1]]
+}
+
+/// Writes `base32(pubkey | checksum | version).onion` into `buf`.
+fn encode_onion_v3(pubkey: &[u8; 32], buf: &mut [u8; ONION_V3_LEN + ONION_SUFFIX.len()]) {
+    let checksum = onion_v3_checksum(pubkey);
+    let bytes = pubkey.iter().chain(&checksum).chain(&[ONION_V3_VERSION]);
+    let mut acc = 0u16;
+    let mut bits = 0;
+    let mut pos = 0;
+    for &byte in bytes {
+        acc = (acc << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            buf[pos] = BASE32_ALPHABET[usize::from((acc >> bits) & 0x1f)];
+            pos += 1;
+        }
+    }
+    // 35 bytes are exactly 56 base32 characters so there are no bits left over
+    debug_assert_eq!(bits, 0);
+    buf[ONION_V3_LEN..].copy_from_slice(ONION_SUFFIX.as_bytes());
+}
+
+/// Returns the label preceding `.onion` (case-insensitive) if the host is an onion address.
+fn strip_onion_suffix(host: &str) -> Option<&str> {
+    let split = host.len().checked_sub(ONION_SUFFIX.len())?;
+    if host.is_char_boundary(split) && host[split..].eq_ignore_ascii_case(ONION_SUFFIX) {
+        Some(&host[..split])
+    } else {
+        None
+    }
+}
+
+/// Decodes and validates onion v3 address without the `.onion` suffix.
+fn decode_onion_v3(label: &str) -> Result<[u8; 32], OnionError> {
+    if label.contains('.') {
+        return Err(OnionError::Subdomain);
+    }
+    if label.len() == ONION_V2_LEN {
+        return Err(OnionError::V2);
+    }
+    if label.len() != ONION_V3_LEN {
+        return Err(OnionError::InvalidLength(label.len()));
+    }
+
+    let mut bytes = [0u8; 35];
+    let mut acc = 0u16;
+    let mut bits = 0;
+    let mut pos = 0;
+    for (i, c) in label.chars().enumerate() {
+        let value = match c {
+            'a'..='z' => c as u8 - b'a',
+            'A'..='Z' => c as u8 - b'A',
+            '2'..='7' => c as u8 - b'2' + 26,
+            _ => return Err(OnionError::InvalidChar { pos: i, c }),
+        };
+        acc = (acc << 5) | u16::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes[pos] = (acc >> bits) as u8;
+            pos += 1;
+        }
+    }
+
+    let mut pubkey = [0u8; 32];
+    pubkey.copy_from_slice(&bytes[..32]);
+    if bytes[34] != ONION_V3_VERSION {
+        return Err(OnionError::UnsupportedVersion(bytes[34]));
+    }
+    if bytes[32..34] != onion_v3_checksum(&pubkey) {
+        return Err(OnionError::InvalidChecksum);
+    }
+    Ok(pubkey)
+}
+
+/// Checks that the hostname is valid according to RFC 1123.
+///
+/// Additionally, the hostname must be at most 255 bytes long (BOLT 7 limit) and the last label
+/// must not be numeric to avoid accepting invalid IPv4 addresses such as `1.2.3.256`.
+///
+/// Fully-qualified names with a trailing dot (`example.com.`) are rejected even though DNS allows
+/// them so that each host has a single representation.
+fn validate_hostname(hostname: &str) -> Result<(), HostnameError> {
+    if hostname.is_empty() {
+        return Err(HostnameError::Empty);
+    }
+    if hostname.len() > MAX_HOSTNAME_LEN {
+        return Err(HostnameError::TooLong(hostname.len()));
+    }
+    if hostname.ends_with('.') {
+        return Err(HostnameError::TrailingDot);
+    }
+
+    let mut label_start = 0;
+    for label in hostname.split('.') {
+        if label.is_empty() {
+            return Err(HostnameError::EmptyLabel(label_start));
+        }
+        if label.len() > MAX_LABEL_LEN {
+            return Err(HostnameError::LabelTooLong { pos: label_start, len: label.len() });
+        }
+        if let Some((i, c)) = label.char_indices().find(|(_, c)| !c.is_ascii_alphanumeric() && *c != '-') {
+            return Err(HostnameError::InvalidChar { pos: label_start + i, c });
+        }
+        if label.starts_with('-') {
+            return Err(HostnameError::LeadingHyphen(label_start));
         }
+        if label.ends_with('-') {
+            return Err(HostnameError::TrailingHyphen(label_start));
+        }
+        label_start += label.len() + 1;
+    }
+
+    let last_label = &hostname[hostname.rfind('.').map_or(0, |pos| pos + 1)..];
+    if last_label.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(HostnameError::NumericTld);
+    }
+    Ok(())
+}
+
+/// Converts the host part of `host_port` to ASCII using IDNA.
+///
+/// Bracketed IPv6 addresses are returned unchanged.
+#[cfg(feature = "idna")]
+fn normalize_idna(host_port: &str) -> Result<String, ParseErrorInner> {
+    if host_port.starts_with('[') {
+        return Ok(host_port.to_owned());
+    }
+    let host_end = host_port.rfind(':').unwrap_or(host_port.len());
+    let (host, port) = host_port.split_at(host_end);
+    let mut normalized = idna::domain_to_ascii(host).map_err(|_| ParseErrorInner::InvalidIdna)?;
+    normalized.push_str(port);
+    Ok(normalized)
+}
+
+/// Validates hostname coming from a source that has separate representations for IP and onion
+/// addresses.
+fn validate_pure_hostname(hostname: &str) -> Result<(), HostnameError> {
+    if hostname.parse::<net::IpAddr>().is_ok() || strip_onion_suffix(hostname).is_some() {
+        return Err(HostnameError::NotHostname);
     }
+    validate_hostname(hostname)
 }
 
 /// Helper struct that can be used to correctly display `host:port`
 ///
 /// This is needed because IPv6 addresses need square brackets when displayed as `ip:port` but
 /// square brackets are not used when they are displayed standalone.
+///
+/// `HostPort<Host>` can also be parsed from `host:port` string using the same rules as
+/// [`P2PAddress`] - IPv6 addresses must be in square brackets and the port defaults to 9735.
+/// Bracketed IPv6 addresses may contain numeric zone ID such as `[fe80::1%2]`. Interface names
+/// (`[fe80::1%eth0]`) are rejected because their meaning depends on the machine.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::p2p_address::{Host, HostPort};
+///
+/// let host_port = "[::1]".parse::<HostPort<Host>>().unwrap();
+/// assert!(host_port.0.is_ip_addr());
+/// assert_eq!(host_port.1, 9735);
+/// assert_eq!(host_port.to_string(), "[::1]:9735");
+/// ```
+#[derive(Clone)]
 pub struct HostPort<H: Borrow<Host>>(
     /// Host
     ///
@@ -126,32 +388,42 @@ pub struct HostPort<H: Borrow<Host>>(
 impl<H: Borrow<Host>> fmt::Display for HostPort<H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.0.borrow().0 {
-            HostInner::Ip(net::IpAddr::V6(addr)) => write!(f, "[{}]:{}", addr, self.1),
+            HostInner::Ip(net::IpAddr::V6(_)) | HostInner::Ipv6Scoped(_, _) => write!(f, "[{}]:{}", self.0.borrow(), self.1),
             _ => write!(f, "{}:{}", self.0.borrow(), self.1),
         }
     }
 }
 
+/// Same as Display
+impl<H: Borrow<Host>> fmt::Debug for HostPort<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
 #[cfg(feature = "alloc")]
 impl From<Host> for String {
     fn from(value: Host) -> Self {
         match value.0 {
             HostInner::Ip(ip_addr) => ip_addr.to_string(),
-            #[cfg(feature = "alloc")]
-            HostInner::Hostname(hostname) => hostname,
+            HostInner::Hostname(hostname) => hostname.as_str().to_owned(),
+            HostInner::Ipv6Scoped(_, _) | HostInner::OnionV3(_) => value.to_string(),
         }
     }
 }
 
 /// This does **not** attempt to resolve a hostname!
+///
+/// The scope ID of IPv6 address is dropped, use [`Host::ipv6_scope_id`] or
+/// [`ToSocketAddrs`](std::net::ToSocketAddrs) to preserve it.
 impl TryFrom<Host> for net::IpAddr {
     type Error = NotIpAddr;
 
     fn try_from(value: Host) -> Result<Self, Self::Error> {
         match value.0 {
             HostInner::Ip(ip_addr) => Ok(ip_addr),
-            #[cfg(feature = "alloc")]
-            HostInner::Hostname(hostname) => Err(NotIpAddr(hostname)),
+            HostInner::Ipv6Scoped(ip_addr, _) => Ok(ip_addr.into()),
+            HostInner::Hostname(_) | HostInner::OnionV3(_) => Err(NotIpAddr(value)),
         }
     }
 }
@@ -159,28 +431,19 @@ impl TryFrom<Host> for net::IpAddr {
 /// Error returned when attempting to *convert* (not resolve) hostname to IP address.
 ///
 /// **Important: consumer code MUST NOT match on this using `NotIpAddr { .. }` syntax.
-#[cfg(feature = "alloc")]
 #[derive(Debug)]
-pub struct NotIpAddr(String);
+pub struct NotIpAddr(Host);
 
-/// Error returned when attempting to *convert* (not resolve) hostname to IP address.
-///
-/// **Important: consumer code MUST NOT match on this using `NotIpAddr { .. }` syntax.
-#[derive(Debug)]
-#[cfg(not(feature = "alloc"))]
-#[non_exhaustive]
-pub struct NotIpAddr;
+impl NotIpAddr {
+    /// Returns the host that failed to convert.
+    pub fn into_host(self) -> Host {
+        self.0
+    }
+}
 
 impl fmt::Display for NotIpAddr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        #[cfg(feature = "alloc")]
-        {
-            write!(f, "the hostname '{}' is not an IP address", self.0)
-        }
-        #[cfg(not(feature = "alloc"))]
-        {
-            write!(f, "the hostname is not an IP address")
-        }
+        write!(f, "the hostname '{}' is not an IP address", self.0)
     }
 }
 
@@ -193,23 +456,22 @@ impl std::error::Error for NotIpAddr {}
 /// It can efficiently parse and display the address as well as perform various conversions using
 /// external crates.
 ///
-/// It also stores host in a way that can avoid allocation if it's **not** a host name.
-/// This also means it works without `alloc` feature however it can not be constructed with a
-/// hostname.
+/// It stores host inline so it never allocates and behaves the same without `alloc` feature,
+/// except for errors not containing the input string. The downside is that the type is over 256
+/// bytes large so you may want to box it if you store many of them.
 ///
-/// **Serde limitations:** non-human-readable formats are not supported yet as it wasn't decided
-/// what's the best way of doing it. Please state your preference in GitHub issues.
+/// Serde serializes the address as a string to human-readable formats and as a compact tuple of
+/// node ID, tagged host and port to non-human-readable formats.
 ///
 /// # Example
 ///
 /// ```
-/// # #[cfg(feature = "alloc")] {
 /// let marvin_str = "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482@ln-ask.me";
 /// let marvin = marvin_str.parse::<ln_types::P2PAddress>().unwrap();
+/// # #[cfg(feature = "alloc")]
 /// assert_eq!(marvin.node_id.to_string(), "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482");
 /// assert!(!marvin.host.is_ip_addr());
 /// assert_eq!(marvin.port, 9735);
-/// # }
 /// ```
 #[derive(Clone)]
 pub struct P2PAddress {
@@ -221,18 +483,6 @@ pub struct P2PAddress {
     pub port: u16,
 }
 
-/// Intermediate representation of host.
-///
-/// This stores range representing host instead of string directly so that it can be returned from
-/// a monomorphic function without requiring allocations.
-enum IpOrHostnamePos {
-    Ip(net::IpAddr),
-    #[cfg(feature = "alloc")]
-    Hostname(usize, usize),
-    #[cfg(not(feature = "alloc"))]
-    Hostname((), ()),
-}
-
 impl P2PAddress {
     /// Conveniently constructs [`HostPort`].
     ///
@@ -245,38 +495,13 @@ impl P2PAddress {
     /// Internal monomorphic parsing method.
     ///
     /// This should improve codegen without requiring allocations.
-    fn parse_raw(s: &str) -> Result<(NodeId, IpOrHostnamePos, u16), ParseErrorInner> {
+    fn parse_raw(s: &str) -> Result<(NodeId, HostInner, u16), ParseErrorInner> {
         let at_pos = s.find('@').ok_or(ParseErrorInner::MissingAtSymbol)?;
         let (node_id, host_port) = s.split_at(at_pos);
         let host_port = &host_port[1..];
         let node_id = node_id.parse().map_err(ParseErrorInner::InvalidNodeId)?;
-        let (host_end, port) = match (host_port.starts_with('[') && host_port.ends_with(']'), host_port.rfind(':')) {
-            // The whole thing is an IPv6, without port
-            (true, _) => (host_port.len(), LN_DEFAULT_PORT),
-            (false, Some(pos)) => (pos, host_port[(pos + 1)..].parse().map_err(ParseErrorInner::InvalidPortNumber)?),
-            (false, None) => (host_port.len(), LN_DEFAULT_PORT),
-        };
-        let host = &host_port[..host_end];
-        let host = match host.parse::<net::Ipv4Addr>() {
-            Ok(ip) => IpOrHostnamePos::Ip(ip.into()),
-            // We have to explicitly parse IPv6 without port to avoid confusing `:`
-            Err(_) if host.starts_with('[') && host.ends_with(']') => {
-                let ip = host_port[1..(host.len() - 1)]
-                    .parse::<net::Ipv6Addr>()
-                    .map_err(ParseErrorInner::InvalidIpv6)?;
-
-                IpOrHostnamePos::Ip(ip.into())
-            },
-            #[cfg(feature = "alloc")]
-            Err(_) => {
-                IpOrHostnamePos::Hostname(at_pos + 1, at_pos + 1 + host_end)
-            },
-            #[cfg(not(feature = "alloc"))]
-            Err(_) => {
-                IpOrHostnamePos::Hostname((), ())
-            },
-        };
-        
+        let (host, port) = parse_host_port_raw(host_port)?;
+
         Ok((node_id, host, port))
     }
 
@@ -290,13 +515,6 @@ impl P2PAddress {
                 reason: error,
             }),
         };
-        let host = match host {
-            #[cfg(feature = "alloc")]
-            IpOrHostnamePos::Hostname(begin, end) => HostInner::Hostname(s.into_substring(begin, end)),
-            #[cfg(not(feature = "alloc"))]
-            IpOrHostnamePos::Hostname(_, _) => return Err(ParseError { reason: ParseErrorInner::UnsupportedHostname }),
-            IpOrHostnamePos::Ip(ip) => HostInner::Ip(ip),
-        };
 
         Ok(P2PAddress {
             node_id,
@@ -304,6 +522,103 @@ impl P2PAddress {
             port,
         })
     }
+
+    /// Parses the address converting internationalized hostname to ASCII first.
+    ///
+    /// The host is normalized using the IDNA "domain to ASCII" algorithm (which includes
+    /// punycode encoding and lowercasing) before validating it. The resulting hostname is
+    /// stored in its ASCII form. Plain parsing rejects internationalized hostnames.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let addr = ln_types::P2PAddress::parse_idna("022345678901234567890123456789012345678901234567890123456789abcdef@bücher.example").unwrap();
+    /// assert_eq!(addr.host.to_string(), "xn--bcher-kva.example");
+    /// ```
+    #[cfg(feature = "idna")]
+    pub fn parse_idna(s: &str) -> Result<Self, ParseError> {
+        let host_start = match s.find('@') {
+            Some(at_pos) => at_pos + 1,
+            None => return Self::internal_parse(s),
+        };
+        let normalized = normalize_idna(&s[host_start..])
+            .map_err(|reason| ParseError { input: s.to_owned(), reason })?;
+        let mut normalized_s = String::with_capacity(host_start + normalized.len());
+        normalized_s.push_str(&s[..host_start]);
+        normalized_s.push_str(&normalized);
+        Self::internal_parse(normalized_s)
+            .map_err(|error| ParseError { input: s.to_owned(), reason: error.reason })
+    }
+}
+
+/// Parses port following `:`, an empty port is an error.
+fn parse_port(port: &str) -> Result<u16, ParseErrorInner> {
+    if port.is_empty() {
+        return Err(ParseErrorInner::EmptyPort);
+    }
+    port.parse().map_err(ParseErrorInner::InvalidPortNumber)
+}
+
+/// Parses the content of square brackets - IPv6 address optionally followed by numeric `%zone_id`.
+fn parse_bracketed_ipv6(host: &str) -> Result<HostInner, ParseErrorInner> {
+    let (ip, zone) = match host.find('%') {
+        Some(pos) => (&host[..pos], Some(&host[(pos + 1)..])),
+        None => (host, None),
+    };
+    let ip = ip.parse::<net::Ipv6Addr>().map_err(ParseErrorInner::InvalidIpv6)?;
+    let scope_id = match zone {
+        None => 0,
+        Some("") => return Err(ParseErrorInner::InvalidZoneId(ZoneIdError::Empty)),
+        Some(zone) if zone.bytes().all(|c| c.is_ascii_digit()) => zone.parse().map_err(|error| ParseErrorInner::InvalidZoneId(ZoneIdError::InvalidIndex(error)))?,
+        Some(_) => return Err(ParseErrorInner::InvalidZoneId(ZoneIdError::NonNumeric)),
+    };
+
+    if scope_id == 0 {
+        Ok(HostInner::Ip(ip.into()))
+    } else {
+        Ok(HostInner::Ipv6Scoped(ip, scope_id))
+    }
+}
+
+/// Parses `host:port` or just `host` with default port.
+///
+/// IPv6 addresses must be enclosed in square brackets and may contain numeric zone ID
+/// (`[fe80::1%2]`).
+fn parse_host_port_raw(host_port: &str) -> Result<(HostInner, u16), ParseErrorInner> {
+    if let Some(bracketed) = host_port.strip_prefix('[') {
+        let end = bracketed.find(']').ok_or(ParseErrorInner::MissingClosingBracket)?;
+        let port = match &bracketed[(end + 1)..] {
+            "" => LN_DEFAULT_PORT,
+            rest => match rest.strip_prefix(':') {
+                Some(port) => parse_port(port)?,
+                None => return Err(ParseErrorInner::UnexpectedAfterBracket),
+            },
+        };
+        return Ok((parse_bracketed_ipv6(&bracketed[..end])?, port));
+    }
+
+    let (host, port) = match host_port.find(':') {
+        // More than one colon can only be IPv6, `::1` would otherwise be "successfully" parsed
+        // as hostname with port 1.
+        Some(pos) if host_port[(pos + 1)..].contains(':') => return Err(ParseErrorInner::UnbracketedIpv6),
+        Some(pos) => (&host_port[..pos], parse_port(&host_port[(pos + 1)..])?),
+        None => (host_port, LN_DEFAULT_PORT),
+    };
+    let host = match host.parse::<net::Ipv4Addr>() {
+        Ok(ip) => HostInner::Ip(ip.into()),
+        Err(_) if strip_onion_suffix(host).is_some() => {
+            let label = strip_onion_suffix(host).expect("checked above");
+            let pubkey = decode_onion_v3(label).map_err(ParseErrorInner::InvalidOnion)?;
+
+            HostInner::OnionV3(pubkey)
+        },
+        Err(_) => {
+            validate_hostname(host).map_err(ParseErrorInner::InvalidHostname)?;
+            HostInner::Hostname(Hostname::from_validated(host))
+        },
+    };
+
+    Ok((host, port))
 }
 
 /// Alternative formatting displays node ID in upper case
@@ -362,124 +677,614 @@ impl TryFrom<Box<str>> for P2PAddress {
     }
 }
 
-/// Error returned when parsing text representation fails.
-///
-/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
-#[derive(Debug, Clone)]
-pub struct ParseError {
-    #[cfg(feature = "alloc")]
-    input: String,
-    reason: ParseErrorInner,
-}
+impl HostPort<Host> {
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    fn internal_parse<S: StringOps>(s: S) -> Result<Self, HostPortParseError> {
+        let (host, port) = match parse_host_port_raw(s.as_ref()) {
+            Ok(result) => result,
+            Err(error) => return Err(HostPortParseError {
+                #[cfg(feature = "alloc")]
+                input: s.into(),
+                reason: error,
+            }),
+        };
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        #[cfg(feature = "alloc")]
-        {
-            write_err!(f, "failed to parse '{}' as Lightning Network P2P address", self.input; &self.reason)
-        }
-        #[cfg(not(feature = "alloc"))]
-        {
-            write_err!(f, "failed to parse Lightning Network P2P address"; &self.reason)
-        }
+        Ok(HostPort(Host(host), port))
+    }
+
+    /// Parses host and port converting internationalized hostname to ASCII first.
+    ///
+    /// See [`P2PAddress::parse_idna`] for details.
+    #[cfg(feature = "idna")]
+    pub fn parse_idna(s: &str) -> Result<Self, HostPortParseError> {
+        let normalized = normalize_idna(s)
+            .map_err(|reason| HostPortParseError { input: s.to_owned(), reason })?;
+        Self::internal_parse(normalized)
+            .map_err(|error| HostPortParseError { input: s.to_owned(), reason: error.reason })
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ParseError {
+impl FromStr for HostPort<Host> {
+    type Err = HostPortParseError;
+
     #[inline]
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        if let ParseErrorInner::InvalidNodeId(error) = &self.reason {
-            Some(error)
-        } else {
-            Some(&self.reason)
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
     }
 }
 
-#[derive(Debug, Clone)]
-enum ParseErrorInner {
-    MissingAtSymbol,
-    InvalidNodeId(crate::node_id::ParseError),
-    InvalidPortNumber(core::num::ParseIntError),
-    InvalidIpv6(net::AddrParseError),
-    #[cfg(not(feature = "alloc"))]
-    UnsupportedHostname,
+impl<'a> TryFrom<&'a str> for HostPort<Host> {
+    type Error = HostPortParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
 }
 
-impl fmt::Display for ParseErrorInner {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            ParseErrorInner::MissingAtSymbol => f.write_str("missing '@' symbol"),
-            ParseErrorInner::InvalidNodeId(error) => fmt::Display::fmt(error, f),
-            ParseErrorInner::InvalidPortNumber(error) => write_err!(f, "invalid port number"; error),
-            ParseErrorInner::InvalidIpv6(error) => write_err!(f, "invalid IPv6 address"; error),
-            #[cfg(not(feature = "alloc"))]
-            ParseErrorInner::UnsupportedHostname => f.write_str("the address is a hostname which is unsupported in this build (without an allocator)"),
-        }
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for HostPort<Host> {
+    type Error = HostPortParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ParseErrorInner {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            ParseErrorInner::MissingAtSymbol => None,
-            ParseErrorInner::InvalidNodeId(error) => error.source(),
-            ParseErrorInner::InvalidPortNumber(error) => Some(error),
-            ParseErrorInner::InvalidIpv6(error) => Some(error),
-            #[cfg(not(feature = "alloc"))]
-            ParseErrorInner::UnsupportedHostname => None,
-        }
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for HostPort<Host> {
+    type Error = HostPortParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
     }
 }
 
-/// Iterator over socket addresses returned by `to_socket_addrs()`
+/// All known addresses of a single node.
 ///
-/// This is the iterator used in the implementation of [`std::net::ToSocketAddrs`] for [`HostPort`]
-/// and [`P2PAddress`].
-#[cfg(feature = "std")]
-pub struct SocketAddrs {
-    iter: core::iter::Chain<core::option::IntoIter<net::SocketAddr>, std::vec::IntoIter<net::SocketAddr>>
+/// The text representation is `node_id@host1:port1,host2:port2` which matches how LND and Core
+/// Lightning list the addresses of a node. Each address follows the same rules as
+/// [`HostPort`]. A node without addresses is represented by its node ID only.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::p2p_address::NodeAddresses;
+///
+/// let input = "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482@ln-ask.me,[2001:db8::1]:9736";
+/// let node = input.parse::<NodeAddresses>().unwrap();
+/// assert_eq!(node.addresses.len(), 2);
+/// assert_eq!(node.addresses[1].1, 9736);
+/// assert_eq!(node.to_string(), "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482@ln-ask.me:9735,[2001:db8::1]:9736");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct NodeAddresses {
+    /// The representation of nodes public key
+    pub node_id: NodeId,
+    /// Network addresses of the node
+    pub addresses: Vec<HostPort<Host>>,
 }
 
-#[cfg(feature = "std")]
-impl Iterator for SocketAddrs {
-    type Item = net::SocketAddr;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+#[cfg(feature = "alloc")]
+impl NodeAddresses {
+    /// Returns an iterator over the addresses combined with node ID.
+    pub fn p2p_addresses(&self) -> impl '_ + Iterator<Item=P2PAddress> {
+        self.addresses.iter().map(move |address| P2PAddress {
+            node_id: self.node_id,
+            host: address.0.clone(),
+            port: address.1,
+        })
     }
-}
 
-/// Note that onion addresses can never be resolved, you have to use a proxy instead.
-#[cfg(feature = "std")]
+    /// Internal monomorphic parsing method.
+    fn parse_raw(s: &str) -> Result<Self, NodeAddressesParseErrorInner> {
+        let (node_id, addresses) = match s.find('@') {
+            Some(pos) => (&s[..pos], Some(&s[(pos + 1)..])),
+            None => (s, None),
+        };
+        let node_id = node_id.parse().map_err(NodeAddressesParseErrorInner::InvalidNodeId)?;
+        let addresses = addresses
+            .into_iter()
+            .flat_map(|addresses| addresses.split(','))
+            .enumerate()
+            .map(|(index, address)| {
+                if address.is_empty() {
+                    return Err(NodeAddressesParseErrorInner::EmptyAddress(index));
+                }
+                let (host, port) = parse_host_port_raw(address)
+                    .map_err(|error| NodeAddressesParseErrorInner::InvalidAddress(index, error))?;
+                Ok(HostPort(Host(host), port))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(NodeAddresses {
+            node_id,
+            addresses,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    fn internal_parse<S: StringOps>(s: S) -> Result<Self, NodeAddressesParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|reason| NodeAddressesParseError {
+            input: s.into(),
+            reason,
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<P2PAddress> for NodeAddresses {
+    fn from(value: P2PAddress) -> Self {
+        NodeAddresses {
+            node_id: value.node_id,
+            addresses: alloc::vec![HostPort(value.host, value.port)],
+        }
+    }
+}
+
+/// Alternative formatting displays node ID in upper case
+#[cfg(feature = "alloc")]
+impl fmt::Display for NodeAddresses {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:X}", self.node_id)?;
+        } else {
+            write!(f, "{:x}", self.node_id)?;
+        }
+        let mut separator = '@';
+        for address in &self.addresses {
+            write!(f, "{}{}", separator, address)?;
+            separator = ',';
+        }
+        Ok(())
+    }
+}
+
+/// Same as Display
+#[cfg(feature = "alloc")]
+impl fmt::Debug for NodeAddresses {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for NodeAddresses {
+    type Err = NodeAddressesParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> TryFrom<&'a str> for NodeAddresses {
+    type Error = NodeAddressesParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for NodeAddresses {
+    type Error = NodeAddressesParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for NodeAddresses {
+    type Error = NodeAddressesParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    #[cfg(feature = "alloc")]
+    input: String,
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        {
+            write_err!(f, "failed to parse '{}' as Lightning Network P2P address", self.input; &self.reason)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            write_err!(f, "failed to parse Lightning Network P2P address"; &self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        if let ParseErrorInner::InvalidNodeId(error) = &self.reason {
+            Some(error)
+        } else {
+            Some(&self.reason)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    MissingAtSymbol,
+    InvalidNodeId(crate::node_id::ParseError),
+    InvalidPortNumber(core::num::ParseIntError),
+    InvalidIpv6(net::AddrParseError),
+    InvalidOnion(OnionError),
+    InvalidHostname(HostnameError),
+    EmptyPort,
+    MissingClosingBracket,
+    UnexpectedAfterBracket,
+    UnbracketedIpv6,
+    InvalidZoneId(ZoneIdError),
+    #[cfg(feature = "idna")]
+    InvalidIdna,
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::MissingAtSymbol => f.write_str("missing '@' symbol"),
+            ParseErrorInner::InvalidNodeId(error) => fmt::Display::fmt(error, f),
+            ParseErrorInner::InvalidPortNumber(error) => write_err!(f, "invalid port number"; error),
+            ParseErrorInner::InvalidIpv6(error) => write_err!(f, "invalid IPv6 address"; error),
+            ParseErrorInner::InvalidOnion(error) => write_err!(f, "invalid onion address"; error),
+            ParseErrorInner::InvalidHostname(error) => write_err!(f, "invalid hostname"; error),
+            ParseErrorInner::EmptyPort => f.write_str("the port number is empty"),
+            ParseErrorInner::MissingClosingBracket => f.write_str("missing closing ']'"),
+            ParseErrorInner::UnexpectedAfterBracket => f.write_str("']' must be followed by ':' and port number or nothing"),
+            ParseErrorInner::UnbracketedIpv6 => f.write_str("IPv6 addresses must be enclosed in square brackets"),
+            ParseErrorInner::InvalidZoneId(error) => write_err!(f, "invalid IPv6 zone ID"; error),
+            #[cfg(feature = "idna")]
+            ParseErrorInner::InvalidIdna => f.write_str("invalid internationalized domain name"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseErrorInner::MissingAtSymbol => None,
+            ParseErrorInner::InvalidNodeId(error) => error.source(),
+            ParseErrorInner::InvalidPortNumber(error) => Some(error),
+            ParseErrorInner::InvalidIpv6(error) => Some(error),
+            ParseErrorInner::InvalidOnion(error) => Some(error),
+            ParseErrorInner::InvalidHostname(error) => Some(error),
+            ParseErrorInner::EmptyPort => None,
+            ParseErrorInner::MissingClosingBracket => None,
+            ParseErrorInner::UnexpectedAfterBracket => None,
+            ParseErrorInner::UnbracketedIpv6 => None,
+            ParseErrorInner::InvalidZoneId(error) => Some(error),
+            #[cfg(feature = "idna")]
+            ParseErrorInner::InvalidIdna => None,
+        }
+    }
+}
+
+/// Error returned when parsing text representation of [`HostPort`] fails.
+///
+/// **Important: consumer code MUST NOT match on this using `HostPortParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct HostPortParseError {
+    #[cfg(feature = "alloc")]
+    input: String,
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for HostPortParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        {
+            write_err!(f, "failed to parse '{}' as host and port", self.input; &self.reason)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            write_err!(f, "failed to parse host and port"; &self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HostPortParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// Error returned when parsing text representation of [`NodeAddresses`] fails.
+///
+/// **Important: consumer code MUST NOT match on this using `NodeAddressesParseError { .. }` syntax.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct NodeAddressesParseError {
+    input: String,
+    reason: NodeAddressesParseErrorInner,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for NodeAddressesParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to parse '{}' as Lightning Network node addresses", self.input; &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NodeAddressesParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        if let NodeAddressesParseErrorInner::InvalidNodeId(error) = &self.reason {
+            Some(error)
+        } else {
+            Some(&self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+enum NodeAddressesParseErrorInner {
+    InvalidNodeId(crate::node_id::ParseError),
+    EmptyAddress(usize),
+    InvalidAddress(usize, ParseErrorInner),
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for NodeAddressesParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NodeAddressesParseErrorInner::InvalidNodeId(error) => fmt::Display::fmt(error, f),
+            NodeAddressesParseErrorInner::EmptyAddress(index) => write!(f, "the address at index {} is empty", index),
+            NodeAddressesParseErrorInner::InvalidAddress(index, error) => write_err!(f, "invalid address at index {}", index; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NodeAddressesParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NodeAddressesParseErrorInner::InvalidNodeId(error) => error.source(),
+            NodeAddressesParseErrorInner::EmptyAddress(_) => None,
+            NodeAddressesParseErrorInner::InvalidAddress(_, error) => Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum OnionError {
+    V2,
+    Subdomain,
+    InvalidLength(usize),
+    InvalidChar { pos: usize, c: char },
+    UnsupportedVersion(u8),
+    InvalidChecksum,
+}
+
+impl fmt::Display for OnionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OnionError::V2 => f.write_str("onion v2 addresses are obsolete and not supported"),
+            OnionError::Subdomain => f.write_str("subdomains of onion addresses are not supported"),
+            OnionError::InvalidLength(len) => write!(f, "invalid length {} (must be {} characters before '.onion')", len, ONION_V3_LEN),
+            OnionError::InvalidChar { pos, c } => write!(f, "invalid base32 character '{}' at position {}", c, pos),
+            OnionError::UnsupportedVersion(version) => write!(f, "unsupported version {} (only version {} is supported)", version, ONION_V3_VERSION),
+            OnionError::InvalidChecksum => f.write_str("invalid checksum"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OnionError {}
+
+#[derive(Debug, Clone)]
+enum ZoneIdError {
+    Empty,
+    InvalidIndex(core::num::ParseIntError),
+    NonNumeric,
+}
+
+impl fmt::Display for ZoneIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZoneIdError::Empty => f.write_str("the zone ID is empty"),
+            ZoneIdError::InvalidIndex(error) => write_err!(f, "invalid zone index"; error),
+            ZoneIdError::NonNumeric => f.write_str("the zone ID is not a number (interface names are not accepted)"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ZoneIdError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZoneIdError::InvalidIndex(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum HostnameError {
+    Empty,
+    TooLong(usize),
+    EmptyLabel(usize),
+    LabelTooLong { pos: usize, len: usize },
+    InvalidChar { pos: usize, c: char },
+    LeadingHyphen(usize),
+    TrailingHyphen(usize),
+    TrailingDot,
+    NumericTld,
+    NotAscii,
+    /// IP or onion address used where hostname was expected
+    NotHostname,
+}
+
+impl fmt::Display for HostnameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostnameError::Empty => f.write_str("the hostname is empty"),
+            HostnameError::TooLong(len) => write!(f, "the hostname is {} bytes long but at most {} bytes are allowed", len, MAX_HOSTNAME_LEN),
+            HostnameError::EmptyLabel(pos) => write!(f, "empty label at position {}", pos),
+            HostnameError::LabelTooLong { pos, len } => write!(f, "the label at position {} is {} bytes long but at most {} bytes are allowed", pos, len, MAX_LABEL_LEN),
+            HostnameError::InvalidChar { pos, c } => write!(f, "invalid character '{}' at position {}", c.escape_debug(), pos),
+            HostnameError::LeadingHyphen(pos) => write!(f, "the label at position {} starts with '-'", pos),
+            HostnameError::TrailingHyphen(pos) => write!(f, "the label at position {} ends with '-'", pos),
+            HostnameError::TrailingDot => f.write_str("the hostname ends with '.' (fully-qualified names are not supported)"),
+            HostnameError::NumericTld => f.write_str("the last label is numeric"),
+            HostnameError::NotAscii => f.write_str("the hostname contains non-ASCII bytes"),
+            HostnameError::NotHostname => f.write_str("IP and onion addresses are not hostnames"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HostnameError {}
+
+/// Iterator over socket addresses returned by `to_socket_addrs()`
+///
+/// This is the iterator used in the implementation of [`std::net::ToSocketAddrs`] for [`HostPort`]
+/// and [`P2PAddress`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct SocketAddrs {
+    iter: core::iter::Chain<core::option::IntoIter<net::SocketAddr>, std::vec::IntoIter<net::SocketAddr>>
+}
+
+#[cfg(feature = "std")]
+impl Iterator for SocketAddrs {
+    type Item = net::SocketAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// Note that onion addresses can never be resolved, you have to use a proxy instead - see
+/// [`socks5`].
+#[cfg(feature = "std")]
 impl<H: Borrow<Host>> std::net::ToSocketAddrs for HostPort<H> {
     type Iter = SocketAddrs;
 
     fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
-        if self.0.borrow().is_onion() {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, ResolveOnion));
-        }
+        let iter = match &self.0.borrow().0 {
+            HostInner::Ip(ip_addr) => Some(net::SocketAddr::new(*ip_addr, self.1)).into_iter().chain(Vec::new()),
+            HostInner::Ipv6Scoped(ip_addr, scope_id) => Some(net::SocketAddrV6::new(*ip_addr, self.1, 0, *scope_id).into()).into_iter().chain(Vec::new()),
+            HostInner::Hostname(hostname) => None.into_iter().chain((hostname.as_str(), self.1).to_socket_addrs()?),
+            HostInner::OnionV3(_) => return Err(io::Error::new(io::ErrorKind::InvalidInput, ResolveOnion)),
+        };
+
+        Ok(SocketAddrs {
+            iter,
+        })
+    }
+}
+
+/// Note that onion addresses can never be resolved, you have to use a proxy instead - see
+/// [`socks5`].
+#[cfg(feature = "std")]
+impl std::net::ToSocketAddrs for P2PAddress {
+    type Iter = SocketAddrs;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        HostPort(&self.host, self.port).to_socket_addrs()
+    }
+}
 
+#[cfg(feature = "tokio")]
+impl<H: Borrow<Host>> HostPort<H> {
+    /// Resolves the host to socket addresses using the asynchronous resolver of `tokio`.
+    ///
+    /// This is an async equivalent of [`std::net::ToSocketAddrs`], avoiding blocking of the
+    /// executor thread. As with `ToSocketAddrs`, onion addresses can never be resolved, you have
+    /// to use a proxy instead.
+    pub async fn resolve_async(&self) -> io::Result<SocketAddrs> {
         let iter = match &self.0.borrow().0 {
             HostInner::Ip(ip_addr) => Some(net::SocketAddr::new(*ip_addr, self.1)).into_iter().chain(Vec::new()),
-            HostInner::Hostname(hostname) => None.into_iter().chain((hostname.as_str(), self.1).to_socket_addrs()?),
+            HostInner::Ipv6Scoped(ip_addr, scope_id) => Some(net::SocketAddrV6::new(*ip_addr, self.1, 0, *scope_id).into()).into_iter().chain(Vec::new()),
+            HostInner::Hostname(hostname) => {
+                let addrs = tokio::net::lookup_host((hostname.as_str(), self.1)).await?;
+                None.into_iter().chain(addrs.collect::<Vec<_>>())
+            },
+            HostInner::OnionV3(_) => return Err(io::Error::new(io::ErrorKind::InvalidInput, ResolveOnion)),
         };
 
         Ok(SocketAddrs {
             iter,
         })
     }
+
+    /// Resolves the host and connects to it over TCP.
+    ///
+    /// The resolved addresses are tried in order and the first successful connection is
+    /// returned. If all attempts fail the error of the last one is returned.
+    pub async fn connect_tcp(&self) -> io::Result<tokio::net::TcpStream> {
+        let mut last_error = None;
+        for addr in self.resolve_async().await? {
+            match tokio::net::TcpStream::connect(addr).await {
+                Ok(stream) => return Ok(stream),
+                Err(error) => last_error = Some(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "could not resolve to any addresses")))
+    }
 }
 
-/// Note that onion addresses can never be resolved, you have to use a proxy instead.
-#[cfg(feature = "std")]
-impl std::net::ToSocketAddrs for P2PAddress {
-    type Iter = SocketAddrs;
+#[cfg(feature = "tokio")]
+impl P2PAddress {
+    /// Resolves the host to socket addresses using the asynchronous resolver of `tokio`.
+    ///
+    /// This is an async equivalent of [`std::net::ToSocketAddrs`], avoiding blocking of the
+    /// executor thread. As with `ToSocketAddrs`, onion addresses can never be resolved, you have
+    /// to use a proxy instead.
+    pub async fn resolve_async(&self) -> io::Result<SocketAddrs> {
+        self.as_host_port().resolve_async().await
+    }
 
-    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
-        HostPort(&self.host, self.port).to_socket_addrs()
+    /// Resolves the host and connects to it over TCP.
+    ///
+    /// The resolved addresses are tried in order and the first successful connection is
+    /// returned. If all attempts fail the error of the last one is returned.
+    ///
+    /// Note that this only establishes the TCP connection, the Lightning Network handshake is up
+    /// to the caller.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> std::io::Result<()> {
+    /// let address = "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482@ln-ask.me"
+    ///     .parse::<ln_types::P2PAddress>()
+    ///     .unwrap();
+    /// let stream = address.connect_tcp().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_tcp(&self) -> io::Result<tokio::net::TcpStream> {
+        self.as_host_port().connect_tcp().await
     }
 }
 
@@ -502,20 +1307,35 @@ impl std::error::Error for ResolveOnion {}
 #[cfg(feature = "parse_arg")]
 mod parse_arg_impl {
     use core::fmt;
-    use super::P2PAddress;
+    use super::{P2PAddress, Host, HostPort};
 
     impl parse_arg::ParseArgFromStr for P2PAddress {
         fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
             writer.write_str("a Lightning Network address in the form `nodeid@host:port`")
         }
     }
+
+    impl parse_arg::ParseArgFromStr for HostPort<Host> {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("a network address in the form `host:port`")
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl parse_arg::ParseArgFromStr for super::NodeAddresses {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("Lightning Network node addresses in the form `nodeid@host1:port1,host2:port2`")
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
 mod serde_impl {
     use core::fmt;
-    use super::P2PAddress;
-    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
+    use super::{P2PAddress, Host, HostInner, net};
+    use crate::NodeId;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error, SeqAccess, EnumAccess, VariantAccess, DeserializeSeed}};
+    use serde::ser::SerializeTuple;
     use core::convert::TryInto;
 
     #[cfg(feature = "serde_alloc")]
@@ -527,7 +1347,7 @@ mod serde_impl {
         type Value = P2PAddress;
 
         fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("a 66 digits long hex string")
+            formatter.write_str("a Lightning Network address in the form `nodeid@host:port`")
         }
 
         fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
@@ -544,36 +1364,198 @@ mod serde_impl {
         }
     }
 
-    /// Serialized as string to human-readable formats.
+    const HOST_VARIANTS: &[&str] = &["Ipv4", "Ipv6", "Hostname", "Onion", "Ipv6Scoped"];
+
+    /// Serializes host as an enum in non-human-readable formats.
+    struct BinaryHost<'a>(&'a Host);
+
+    impl<'a> Serialize for BinaryHost<'a> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            match &(self.0).0 {
+                HostInner::Ip(net::IpAddr::V4(ip)) => serializer.serialize_newtype_variant("Host", 0, HOST_VARIANTS[0], &ip.octets()),
+                HostInner::Ip(net::IpAddr::V6(ip)) => serializer.serialize_newtype_variant("Host", 1, HOST_VARIANTS[1], &ip.octets()),
+                HostInner::Hostname(hostname) => serializer.serialize_newtype_variant("Host", 2, HOST_VARIANTS[2], hostname.as_str()),
+                HostInner::OnionV3(pubkey) => serializer.serialize_newtype_variant("Host", 3, HOST_VARIANTS[3], pubkey),
+                HostInner::Ipv6Scoped(ip, scope_id) => serializer.serialize_newtype_variant("Host", 4, HOST_VARIANTS[4], &(ip.octets(), scope_id)),
+            }
+        }
+    }
+
+    enum HostTag {
+        Ipv4,
+        Ipv6,
+        Hostname,
+        Onion,
+        Ipv6Scoped,
+    }
+
+    struct HostTagVisitor;
+
+    impl<'de> Visitor<'de> for HostTagVisitor {
+        type Value = HostTag;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("host type")
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            match v {
+                0 => Ok(HostTag::Ipv4),
+                1 => Ok(HostTag::Ipv6),
+                2 => Ok(HostTag::Hostname),
+                3 => Ok(HostTag::Onion),
+                4 => Ok(HostTag::Ipv6Scoped),
+                _ => Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &"host type index 0 <= i < 5")),
+            }
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            match v {
+                "Ipv4" => Ok(HostTag::Ipv4),
+                "Ipv6" => Ok(HostTag::Ipv6),
+                "Hostname" => Ok(HostTag::Hostname),
+                "Onion" => Ok(HostTag::Onion),
+                "Ipv6Scoped" => Ok(HostTag::Ipv6Scoped),
+                _ => Err(E::unknown_variant(v, HOST_VARIANTS)),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HostTag {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            deserializer.deserialize_identifier(HostTagVisitor)
+        }
+    }
+
+    struct HostnameVisitor;
+
+    impl<'de> Visitor<'de> for HostnameVisitor {
+        type Value = Host;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a hostname")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            // IP and onion addresses have their own variants
+            if super::validate_pure_hostname(v).is_err() {
+                return Err(E::invalid_value(serde::de::Unexpected::Str(v), &self));
+            }
+            Ok(Host(HostInner::Hostname(super::Hostname::from_validated(v))))
+        }
+    }
+
+    impl<'de> DeserializeSeed<'de> for HostnameVisitor {
+        type Value = Host;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: Deserializer<'de> {
+            deserializer.deserialize_str(self)
+        }
+    }
+
+    struct BinaryHostVisitor;
+
+    impl<'de> Visitor<'de> for BinaryHostVisitor {
+        type Value = Host;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("IPv4, IPv6, hostname or onion host")
+        }
+
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error> where A: EnumAccess<'de> {
+            let (tag, variant) = data.variant::<HostTag>()?;
+            match tag {
+                HostTag::Ipv4 => {
+                    let octets = variant.newtype_variant::<[u8; 4]>()?;
+                    Ok(Host(HostInner::Ip(net::Ipv4Addr::from(octets).into())))
+                },
+                HostTag::Ipv6 => {
+                    let octets = variant.newtype_variant::<[u8; 16]>()?;
+                    Ok(Host(HostInner::Ip(net::Ipv6Addr::from(octets).into())))
+                },
+                HostTag::Hostname => variant.newtype_variant_seed(HostnameVisitor),
+                HostTag::Onion => {
+                    let pubkey = variant.newtype_variant::<[u8; 32]>()?;
+                    Ok(Host(HostInner::OnionV3(pubkey)))
+                },
+                HostTag::Ipv6Scoped => {
+                    let (octets, scope_id) = variant.newtype_variant::<([u8; 16], u32)>()?;
+                    let ip = net::Ipv6Addr::from(octets);
+                    if scope_id == 0 {
+                        Ok(Host(HostInner::Ip(ip.into())))
+                    } else {
+                        Ok(Host(HostInner::Ipv6Scoped(ip, scope_id)))
+                    }
+                },
+            }
+        }
+    }
+
+    struct BinaryVisitor;
+
+    impl<'de> Visitor<'de> for BinaryVisitor {
+        type Value = P2PAddress;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a tuple of node ID, host and port")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: SeqAccess<'de> {
+            struct HostSeed;
+
+            impl<'de> DeserializeSeed<'de> for HostSeed {
+                type Value = Host;
+
+                fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error> where D: Deserializer<'de> {
+                    deserializer.deserialize_enum("Host", HOST_VARIANTS, BinaryHostVisitor)
+                }
+            }
+
+            let node_id = seq.next_element::<NodeId>()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let host = seq.next_element_seed(HostSeed)?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+            let port = seq.next_element::<u16>()?.ok_or_else(|| A::Error::invalid_length(2, &self))?;
+
+            Ok(P2PAddress {
+                node_id,
+                host,
+                port,
+            })
+        }
+    }
+
+    /// Serialized as string to human-readable formats and as a tuple to non-human-readable.
     ///
-    /// # Errors
+    /// The tuple contains node ID (33 bytes), host and port (`u16`).
+    /// Host is an enum with these variants:
     ///
-    /// This fails if the format is **not** human-readable because it's not decided how it should
-    /// be done.
+    /// * `Ipv4` (index 0) - 4 bytes
+    /// * `Ipv6` (index 1) - 16 bytes
+    /// * `Hostname` (index 2) - string
+    /// * `Onion` (index 3) - 32 bytes of onion v3 service public key
+    /// * `Ipv6Scoped` (index 4) - tuple of 16 bytes of IPv6 address and `u32` scope ID
     impl Serialize for P2PAddress {
         fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-            use serde::ser::Error;
-
             if serializer.is_human_readable() {
                 serializer.collect_str(self)
             } else {
-                Err(S::Error::custom("serialization is not yet implemented for non-human-readable formats, please file a request"))
+                let mut tuple = serializer.serialize_tuple(3)?;
+                tuple.serialize_element(&self.node_id)?;
+                tuple.serialize_element(&BinaryHost(&self.host))?;
+                tuple.serialize_element(&self.port)?;
+                tuple.end()
             }
         }
     }
 
-    /// Deserialized as string from human-readable formats.
-    ///
-    /// # Errors
+    /// Deserialized as string from human-readable formats and as a tuple from non-human-readable.
     ///
-    /// This fails if the format is **not** human-readable because it's not decided how it should
-    /// be done.
+    /// See the documentation of `Serialize` implementation for the details of binary format.
     impl<'de> Deserialize<'de> for P2PAddress {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
             if deserializer.is_human_readable() {
                 deserializer.deserialize_str(HRVisitor)
             } else {
-                Err(D::Error::custom("deserialization is not yet implemented for non-human-readable formats, please file a request"))
+                deserializer.deserialize_tuple(3, BinaryVisitor)
             }
         }
     }
@@ -645,12 +1627,16 @@ mod slog_impl {
         }
     }
 
-    impl_error_value!(super::ParseError);
+    impl_error_value!(super::ParseError, super::HostPortParseError);
+    #[cfg(feature = "alloc")]
+    impl_error_value!(super::NodeAddressesParseError);
 }
 
 #[cfg(test)]
 mod tests {
-    use super::P2PAddress;
+    use super::{P2PAddress, Host, HostPort};
+    #[cfg(feature = "alloc")]
+    use super::NodeAddresses;
     use alloc::{format, string::ToString};
 
     #[test]
@@ -669,7 +1655,6 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "alloc")]
     fn correct_hostname_no_port() {
         let input = "022345678901234567890123456789012345678901234567890123456789abcdef@example.com";
         let parsed = input.parse::<P2PAddress>().unwrap();
@@ -679,7 +1664,6 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "alloc")]
     fn correct_with_hostname_port() {
         let input = "022345678901234567890123456789012345678901234567890123456789abcdef@example.com:1234";
         let parsed = input.parse::<P2PAddress>().unwrap();
@@ -721,8 +1705,399 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn host_port_ipv4() {
+        let parsed = "127.0.0.1:1234".parse::<HostPort<Host>>().unwrap();
+        assert!(parsed.0.is_ip_addr());
+        assert_eq!(parsed.1, 1234);
+        assert_eq!(parsed.to_string(), "127.0.0.1:1234");
+    }
+
+    #[test]
+    fn host_port_ipv6_default_port() {
+        let parsed = "[::1]".parse::<HostPort<Host>>().unwrap();
+        assert!(parsed.0.is_ip_addr());
+        assert_eq!(parsed.1, 9735);
+        assert_eq!(parsed.to_string(), "[::1]:9735");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn host_port_hostname() {
+        use core::convert::TryFrom;
+
+        let parsed = HostPort::<Host>::try_from(alloc::string::String::from("example.com:1234")).unwrap();
+        assert!(!parsed.0.is_ip_addr());
+        assert_eq!(parsed.0.to_string(), "example.com");
+        assert_eq!(parsed.1, 1234);
+    }
+
+    #[test]
+    fn host_port_grammar() {
+        // (input, Ok(canonical form) or Err(reason))
+        let cases: &[(&str, Result<&str, &str>)] = &[
+            ("127.0.0.1", Ok("127.0.0.1:9735")),
+            ("127.0.0.1:1", Ok("127.0.0.1:1")),
+            ("[::1]", Ok("[::1]:9735")),
+            ("[::1]:1234", Ok("[::1]:1234")),
+            ("[fe80::1%2]:1234", Ok("[fe80::1%2]:1234")),
+            ("[fe80::1%0]", Ok("[fe80::1]:9735")),
+            ("example.com:1", Ok("example.com:1")),
+            ("::1", Err("IPv6 addresses must be enclosed in square brackets")),
+            ("::1:9735", Err("IPv6 addresses must be enclosed in square brackets")),
+            ("fe80::1", Err("IPv6 addresses must be enclosed in square brackets")),
+            ("127.0.0.1:", Err("the port number is empty")),
+            ("[::1]:", Err("the port number is empty")),
+            ("example.com:", Err("the port number is empty")),
+            ("127.0.0.1:65536", Err("invalid port number")),
+            ("[::1", Err("missing closing ']'")),
+            ("[::1]1234", Err("']' must be followed by ':' and port number or nothing")),
+            ("[::1]]:1234", Err("']' must be followed by ':' and port number or nothing")),
+            ("[127.0.0.1]", Err("invalid IPv6 address")),
+            ("[]", Err("invalid IPv6 address")),
+            ("[fe80::1%]", Err("invalid IPv6 zone ID")),
+            ("[fe80::1%4294967296]", Err("invalid IPv6 zone ID")),
+            ("[fe80::1%eth0]", Err("invalid IPv6 zone ID")),
+            ("[fe80::1%+2]", Err("invalid IPv6 zone ID")),
+            ("", Err("invalid hostname")),
+            (":1234", Err("invalid hostname")),
+            ("::1]", Err("IPv6 addresses must be enclosed in square brackets")),
+        ];
+
+        for (input, expected) in cases {
+            match (input.parse::<HostPort<Host>>(), expected) {
+                (Ok(parsed), Ok(expected)) => assert_eq!(parsed.to_string(), *expected, "input: {}", input),
+                (Err(error), Err(expected)) => assert!(error.reason.to_string().starts_with(expected), "input: {}, error: {}", input, error.reason),
+                (Ok(parsed), Err(_)) => panic!("{} unexpectedly parsed as {}", input, parsed),
+                (Err(error), Ok(_)) => panic!("{} failed to parse: {:?}", input, error),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn ipv6_zone_socket_addr() {
+        use std::net::ToSocketAddrs;
+
+        let parsed = "[fe80::1%2]:1234".parse::<HostPort<Host>>().unwrap();
+        assert_eq!(parsed.0.ipv6_scope_id(), Some(2));
+        match parsed.to_socket_addrs().unwrap().next().unwrap() {
+            std::net::SocketAddr::V6(addr) => assert_eq!(addr.scope_id(), 2),
+            addr => panic!("unexpected address {}", addr),
+        }
+    }
+
+    #[test]
+    fn hostname_not_ip_addr() {
+        use core::convert::TryFrom;
+
+        let parsed = "example.com".parse::<HostPort<Host>>().unwrap();
+        let error = super::net::IpAddr::try_from(parsed.0).unwrap_err();
+        assert_eq!(error.to_string(), "the hostname 'example.com' is not an IP address");
+        assert_eq!(error.into_host().to_string(), "example.com");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn node_addresses() {
+        let input = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234,[::1],example.com:9736";
+        let parsed = input.parse::<NodeAddresses>().unwrap();
+        assert_eq!(parsed.addresses.len(), 3);
+        assert_eq!(parsed.to_string(), "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234,[::1]:9735,example.com:9736");
+        let p2p_addresses = parsed.p2p_addresses().map(|address| address.to_string()).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(p2p_addresses, [
+            "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234",
+            "022345678901234567890123456789012345678901234567890123456789abcdef@[::1]:9735",
+            "022345678901234567890123456789012345678901234567890123456789abcdef@example.com:9736",
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn node_addresses_without_addresses() {
+        let input = "022345678901234567890123456789012345678901234567890123456789abcdef";
+        let parsed = input.parse::<NodeAddresses>().unwrap();
+        assert!(parsed.addresses.is_empty());
+        assert_eq!(parsed.to_string(), input);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn node_addresses_errors() {
+        let error = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1,,[::1]".parse::<NodeAddresses>().unwrap_err();
+        assert!(error.to_string().starts_with("failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1,,[::1]' as Lightning Network node addresses"));
+        let error = "022345678901234567890123456789012345678901234567890123456789abcdef@".parse::<NodeAddresses>().unwrap_err();
+        assert!(error.to_string().starts_with("failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@' as Lightning Network node addresses"));
+
+        #[cfg(feature = "std")]
+        {
+            use std::error::Error;
+
+            let error = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1,[::1]:x".parse::<NodeAddresses>().unwrap_err();
+            let source = error.source().unwrap();
+            assert_eq!(source.to_string(), "invalid address at index 1");
+            assert_eq!(source.source().unwrap().to_string(), "invalid port number");
+        }
+    }
+
+    const ONION: &str = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion";
+
+    #[test]
+    fn onion_v3() {
+        let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}:1234", ONION);
+        let parsed = input.parse::<P2PAddress>().unwrap();
+        assert!(parsed.host.is_onion());
+        assert!(!parsed.host.is_ip_addr());
+        let expected_pubkey = [
+            0x1d, 0x04, 0xa1, 0xd0, 0x4a, 0x33, 0x8c, 0x6e, 0x6a, 0xe9, 0x70, 0xbf, 0xab, 0xee, 0x49, 0x04,
+            0x9d, 0x67, 0x02, 0x25, 0x09, 0x84, 0xca, 0x95, 0x0c, 0x01, 0x67, 0x3f, 0x4e, 0xc0, 0x34, 0xad,
+        ];
+        assert_eq!(parsed.host.onion_v3_pubkey(), Some(&expected_pubkey));
+        assert_eq!(parsed.to_string(), input);
+        assert_eq!(Host::from_onion_v3_pubkey(expected_pubkey).to_string(), ONION);
+    }
+
+    #[test]
+    fn onion_v3_upper_case() {
+        let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}", ONION.to_uppercase());
+        let parsed = input.parse::<P2PAddress>().unwrap();
+        assert_eq!(parsed.host.to_string(), ONION);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn onion_v3_not_resolved() {
+        use std::net::ToSocketAddrs;
+
+        let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}", ONION);
+        let parsed = input.parse::<P2PAddress>().unwrap();
+        assert!(parsed.to_socket_addrs().is_err());
+    }
+
+    #[cfg(feature = "tokio")]
+    fn block_on<F: core::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn connect_tcp_ip() {
+        block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:{}", port);
+            let address = input.parse::<P2PAddress>().unwrap();
+
+            let resolved = address.resolve_async().await.unwrap().collect::<alloc::vec::Vec<_>>();
+            assert_eq!(resolved, [listener.local_addr().unwrap()]);
+
+            // The kernel completes the handshake before `accept()` is called
+            let stream = address.connect_tcp().await.unwrap();
+            let (_, peer_addr) = listener.accept().await.unwrap();
+            assert_eq!(stream.local_addr().unwrap(), peer_addr);
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn connect_tcp_hostname_tries_addresses_in_order() {
+        block_on(async {
+            // localhost may resolve to `::1` first which is not listening so the IPv4 address
+            // has to be tried next
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@localhost:{}", port);
+            let address = input.parse::<P2PAddress>().unwrap();
+
+            let resolved = address.resolve_async().await.unwrap().collect::<alloc::vec::Vec<_>>();
+            assert!(resolved.contains(&listener.local_addr().unwrap()));
+
+            // The kernel completes the handshake before `accept()` is called
+            let stream = address.connect_tcp().await.unwrap();
+            let (_, peer_addr) = listener.accept().await.unwrap();
+            assert_eq!(stream.local_addr().unwrap(), peer_addr);
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn connect_tcp_refused() {
+        block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            drop(listener);
+            let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:{}", port);
+            let address = input.parse::<P2PAddress>().unwrap();
+            let error = address.connect_tcp().await.unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::ConnectionRefused);
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn resolve_async_onion() {
+        block_on(async {
+            let input = format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}", ONION);
+            let address = input.parse::<P2PAddress>().unwrap();
+            let error = address.resolve_async().await.unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+            assert_eq!(error.to_string(), "attempt to resolve onion address");
+            let error = address.connect_tcp().await.unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    fn check_binary_roundtrip(input: &str) {
+        let address = input.parse::<P2PAddress>().unwrap();
+
+        let bytes = bincode::serialize(&address).unwrap();
+        let deserialized = bincode::deserialize::<P2PAddress>(&bytes).unwrap();
+        assert_eq!(deserialized.to_string(), address.to_string());
+
+        let bytes = postcard::to_allocvec(&address).unwrap();
+        let deserialized = postcard::from_bytes::<P2PAddress>(&bytes).unwrap();
+        assert_eq!(deserialized.to_string(), address.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_ipv4() {
+        let input = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234";
+        check_binary_roundtrip(input);
+
+        let address = input.parse::<P2PAddress>().unwrap();
+        let bytes = postcard::to_allocvec(&address).unwrap();
+        // length-prefixed 33 bytes of node ID, host tag, 4 bytes of IP address, varint port
+        assert_eq!(bytes.len(), 1 + 33 + 1 + 4 + 2);
+        assert_eq!(bytes[0], 33);
+        assert_eq!(bytes[1..34], address.node_id.to_array()[..]);
+        assert_eq!(bytes[34..39], [0, 127, 0, 0, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_ipv6() {
+        check_binary_roundtrip("022345678901234567890123456789012345678901234567890123456789abcdef@[::1]:1234");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_ipv6_scoped() {
+        check_binary_roundtrip("022345678901234567890123456789012345678901234567890123456789abcdef@[fe80::1%3]:1234");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_hostname() {
+        check_binary_roundtrip("022345678901234567890123456789012345678901234567890123456789abcdef@example.com:9735");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_onion() {
+        check_binary_roundtrip(&format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}:9735", ONION));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_binary_invalid_host_tag() {
+        let input = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234";
+        let address = input.parse::<P2PAddress>().unwrap();
+        let mut bytes = postcard::to_allocvec(&address).unwrap();
+        bytes[34] = 42;
+        assert!(postcard::from_bytes::<P2PAddress>(&bytes).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_human_readable_roundtrip() {
+        let input = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234";
+        let address = input.parse::<P2PAddress>().unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, format!("\"{}\"", input));
+        let deserialized = serde_json::from_str::<P2PAddress>(&json).unwrap();
+        assert_eq!(deserialized.to_string(), input);
+    }
+
     chk_err_impl! {
+        parse_host_port_error_invalid_port, "127.0.0.1:foo", HostPort<Host>, [
+            "failed to parse '127.0.0.1:foo' as host and port",
+            "invalid port number",
+            "invalid digit found in string",
+        ], [
+            "failed to parse host and port",
+            "invalid port number",
+            "invalid digit found in string",
+        ];
+        parse_host_port_error_zone_interface_name, "[fe80::1%lo]:1234", HostPort<Host>, [
+            "failed to parse '[fe80::1%lo]:1234' as host and port",
+            "invalid IPv6 zone ID",
+            "the zone ID is not a number (interface names are not accepted)",
+        ], [
+            "failed to parse host and port",
+            "invalid IPv6 zone ID",
+            "the zone ID is not a number (interface names are not accepted)",
+        ];
         parse_p2p_address_error_empty, "", P2PAddress, ["failed to parse '' as Lightning Network P2P address", "missing '@' symbol"], ["failed to parse Lightning Network P2P address", "missing '@' symbol"];
+        parse_p2p_address_error_onion_v2, "022345678901234567890123456789012345678901234567890123456789abcdef@expyuzz4wqqyqhjn.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@expyuzz4wqqyqhjn.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "onion v2 addresses are obsolete and not supported",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "onion v2 addresses are obsolete and not supported",
+        ];
+        parse_p2p_address_error_onion_length, "022345678901234567890123456789012345678901234567890123456789abcdef@foo.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@foo.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "invalid length 3 (must be 56 characters before '.onion')",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "invalid length 3 (must be 56 characters before '.onion')",
+        ];
+        parse_p2p_address_error_onion_subdomain, "022345678901234567890123456789012345678901234567890123456789abcdef@www.duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@www.duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "subdomains of onion addresses are not supported",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "subdomains of onion addresses are not supported",
+        ];
+        parse_p2p_address_error_onion_char, "022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzcza1.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzcza1.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "invalid base32 character '1' at position 55",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "invalid base32 character '1' at position 55",
+        ];
+        parse_p2p_address_error_onion_version, "022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczae.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczae.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "unsupported version 4 (only version 3 is supported)",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "unsupported version 4 (only version 3 is supported)",
+        ];
+        parse_p2p_address_error_onion_checksum, "022345678901234567890123456789012345678901234567890123456789abcdef@euckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion", P2PAddress, [
+            "failed to parse '022345678901234567890123456789012345678901234567890123456789abcdef@euckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion' as Lightning Network P2P address",
+            "invalid onion address",
+            "invalid checksum",
+        ], [
+            "failed to parse Lightning Network P2P address",
+            "invalid onion address",
+            "invalid checksum",
+        ];
         parse_p2p_address_error_empty_node_id, "@127.0.0.1", P2PAddress, [
             "failed to parse '@127.0.0.1' as Lightning Network P2P address",
             "failed to parse '' as Lightning Network node ID",
@@ -732,5 +2107,111 @@ mod tests {
             "failed to parse Lightning Network node ID",
             "invalid length (must be 66 chars)",
         ];
+        parse_host_port_error_hostname_leading_hyphen, "-foo.example.com:9735", HostPort<Host>, [
+            "failed to parse '-foo.example.com:9735' as host and port",
+            "invalid hostname",
+            "the label at position 0 starts with '-'",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "the label at position 0 starts with '-'",
+        ];
+        parse_host_port_error_hostname_trailing_hyphen, "foo.example-.com:9735", HostPort<Host>, [
+            "failed to parse 'foo.example-.com:9735' as host and port",
+            "invalid hostname",
+            "the label at position 4 ends with '-'",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "the label at position 4 ends with '-'",
+        ];
+        parse_host_port_error_hostname_empty_label, "foo..com:9735", HostPort<Host>, [
+            "failed to parse 'foo..com:9735' as host and port",
+            "invalid hostname",
+            "empty label at position 4",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "empty label at position 4",
+        ];
+        parse_host_port_error_hostname_trailing_dot, "example.com.:9735", HostPort<Host>, [
+            "failed to parse 'example.com.:9735' as host and port",
+            "invalid hostname",
+            "the hostname ends with '.' (fully-qualified names are not supported)",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "the hostname ends with '.' (fully-qualified names are not supported)",
+        ];
+        parse_host_port_error_hostname_invalid_char, "foo_bar.com:9735", HostPort<Host>, [
+            "failed to parse 'foo_bar.com:9735' as host and port",
+            "invalid hostname",
+            "invalid character '_' at position 3",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "invalid character '_' at position 3",
+        ];
+        parse_host_port_error_hostname_non_ascii, "bücher.example:9735", HostPort<Host>, [
+            "failed to parse 'bücher.example:9735' as host and port",
+            "invalid hostname",
+            "invalid character 'ü' at position 1",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "invalid character 'ü' at position 1",
+        ];
+        parse_host_port_error_hostname_numeric_tld, "1.2.3.256:9735", HostPort<Host>, [
+            "failed to parse '1.2.3.256:9735' as host and port",
+            "invalid hostname",
+            "the last label is numeric",
+        ], [
+            "failed to parse host and port",
+            "invalid hostname",
+            "the last label is numeric",
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "idna")]
+    fn parse_idna() {
+        let addr = HostPort::<Host>::parse_idna("Bücher.example:1234").unwrap();
+        assert_eq!(addr.to_string(), "xn--bcher-kva.example:1234");
+        let addr = HostPort::<Host>::parse_idna("[::1]:1234").unwrap();
+        assert_eq!(addr.to_string(), "[::1]:1234");
+        let addr = P2PAddress::parse_idna("022345678901234567890123456789012345678901234567890123456789abcdef@bücher.example").unwrap();
+        assert_eq!(addr.host.to_string(), "xn--bcher-kva.example");
+        assert_eq!(addr.port, 9735);
+
+        assert!("bücher.example:1234".parse::<HostPort<Host>>().is_err());
+        let error = HostPort::<Host>::parse_idna("xn--a.example:1234").unwrap_err();
+        assert_eq!(error.to_string(), "failed to parse 'xn--a.example:1234' as host and port");
+        assert_eq!(std::error::Error::source(&error).unwrap().to_string(), "invalid internationalized domain name");
+        let error = HostPort::<Host>::parse_idna("foo_bar.example:1234").unwrap_err();
+        assert_eq!(error.to_string(), "failed to parse 'foo_bar.example:1234' as host and port");
+        assert_eq!(std::error::Error::source(&error).unwrap().to_string(), "invalid hostname");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn hostname_length_limits() {
+        let label = "a".repeat(63);
+        let max = format!("{}.{}.{}.{}", label, label, label, "b".repeat(63));
+        assert_eq!(max.len(), 255);
+        max.parse::<HostPort<Host>>().unwrap();
+
+        let too_long = format!("{}c", max);
+        let error = too_long.parse::<HostPort<Host>>().unwrap_err();
+        match error.reason {
+            super::ParseErrorInner::InvalidHostname(super::HostnameError::TooLong(256)) => (),
+            error => panic!("unexpected error: {:?}", error),
+        }
+
+        let long_label = format!("{}a.com", label);
+        let error = long_label.parse::<HostPort<Host>>().unwrap_err();
+        match error.reason {
+            super::ParseErrorInner::InvalidHostname(super::HostnameError::LabelTooLong { pos: 0, len: 64 }) => (),
+            error => panic!("unexpected error: {:?}", error),
+        }
     }
 }
@@ -0,0 +1,482 @@
+//! BOLT 7 address descriptors
+//!
+//! `node_announcement` gossip messages carry the addresses of the node as a list of address
+//! descriptors. Each descriptor starts with a type byte followed by the address and a big-endian
+//! port. The types supported by this module are:
+//!
+//! * `1` - IPv4 address (4 bytes)
+//! * `2` - IPv6 address (16 bytes)
+//! * `4` - Tor v3 onion service (32 bytes of public key, 2 bytes of checksum, 1 byte of version)
+//! * `5` - DNS hostname (1 byte of length followed by ASCII hostname)
+//!
+//! The functions here work with the bytes of the address list only - without the `addrlen` prefix.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use ln_types::p2p_address::descriptor;
+//!
+//! let bytes = [1, 127, 0, 0, 1, 0x26, 0x07];
+//! let addresses = descriptor::decode(&bytes).collect::<Result<Vec<_>, _>>().unwrap();
+//! assert_eq!(addresses.len(), 1);
+//! assert_eq!(addresses[0].to_string(), "127.0.0.1:9735");
+//!
+//! let mut encoded = Vec::new();
+//! addresses[0].encode_descriptor(&mut encoded).unwrap();
+//! assert_eq!(encoded, bytes);
+//! # }
+//! ```
+
+use core::borrow::Borrow;
+use core::fmt;
+use super::{Host, HostInner, HostPort, Hostname, HostnameError, OnionError, net, ONION_V3_VERSION};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+const TYPE_IPV4: u8 = 1;
+const TYPE_IPV6: u8 = 2;
+const TYPE_TOR_V2: u8 = 3;
+const TYPE_TOR_V3: u8 = 4;
+const TYPE_DNS: u8 = 5;
+
+/// Length of obsolete Tor v2 descriptor payload which is skipped when decoding.
+const TOR_V2_LEN: usize = 12;
+
+impl<H: Borrow<Host>> HostPort<H> {
+    /// Returns the number of bytes [`encode_descriptor`](Self::encode_descriptor) appends,
+    /// including the type byte.
+    pub fn descriptor_len(&self) -> usize {
+        1 + match &self.0.borrow().0 {
+            HostInner::Ip(net::IpAddr::V4(_)) => 4,
+            HostInner::Ip(net::IpAddr::V6(_)) | HostInner::Ipv6Scoped(_, _) => 16,
+            HostInner::Hostname(hostname) => 1 + hostname.as_str().len(),
+            HostInner::OnionV3(_) => 35,
+        } + 2
+    }
+
+    /// Appends BOLT 7 address descriptor to `out`.
+    ///
+    /// This fails if the host is a hostname longer than 255 bytes which can not be encoded.
+    /// Hostnames are length-checked when parsed so this currently can not happen.
+    #[cfg(feature = "alloc")]
+    pub fn encode_descriptor(&self, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+        match &self.0.borrow().0 {
+            HostInner::Ip(net::IpAddr::V4(ip)) => {
+                out.push(TYPE_IPV4);
+                out.extend_from_slice(&ip.octets());
+            },
+            // Scope ID is only meaningful locally so it is dropped
+            HostInner::Ip(net::IpAddr::V6(ip)) | HostInner::Ipv6Scoped(ip, _) => {
+                out.push(TYPE_IPV6);
+                out.extend_from_slice(&ip.octets());
+            },
+            HostInner::Hostname(hostname) => {
+                let hostname = hostname.as_str();
+                if hostname.len() > super::MAX_HOSTNAME_LEN {
+                    return Err(EncodeError { len: hostname.len() });
+                }
+                out.push(TYPE_DNS);
+                out.push(hostname.len() as u8);
+                out.extend_from_slice(hostname.as_bytes());
+            },
+            HostInner::OnionV3(pubkey) => {
+                out.push(TYPE_TOR_V3);
+                out.extend_from_slice(pubkey);
+                out.extend_from_slice(&super::onion_v3_checksum(pubkey));
+                out.push(ONION_V3_VERSION);
+            },
+        }
+        out.extend_from_slice(&self.1.to_be_bytes());
+        Ok(())
+    }
+}
+
+/// Decodes a list of address descriptors.
+///
+/// The returned iterator yields the addresses in the order they appear in `bytes`.
+/// Obsolete Tor v2 descriptors are skipped. As required by BOLT 7, decoding stops at the first
+/// descriptor of unknown type because its length is not known. After an error is returned the
+/// iterator ends.
+pub fn decode(bytes: &[u8]) -> Decode<'_> {
+    Decode {
+        bytes,
+        offset: 0,
+        failed: false,
+    }
+}
+
+/// Iterator over decoded address descriptors.
+///
+/// This is returned from [`decode`].
+#[derive(Debug, Clone)]
+pub struct Decode<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    failed: bool,
+}
+
+impl<'a> Decode<'a> {
+    /// Returns the bytes that were not decoded yet.
+    ///
+    /// This is empty if the whole list was decoded and starts with the type byte if decoding
+    /// stopped at an unknown descriptor type or failed.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    /// Takes `len` bytes of payload of descriptor with given type.
+    fn take(&mut self, descriptor_type: u8, len: usize) -> Result<&'a [u8], DecodeError> {
+        let available = self.bytes.len() - self.offset - 1;
+        if available < len {
+            return Err(DecodeError {
+                offset: self.offset,
+                reason: DecodeErrorInner::Truncated { descriptor_type, expected: 1 + len, available: 1 + available },
+            });
+        }
+        let payload = &self.bytes[(self.offset + 1)..(self.offset + 1 + len)];
+        self.offset += 1 + len;
+        Ok(payload)
+    }
+
+    fn error(&self, reason: DecodeErrorInner) -> DecodeError {
+        DecodeError {
+            offset: self.offset,
+            reason,
+        }
+    }
+
+    fn decode_next(&mut self) -> Result<Option<HostPort<Host>>, DecodeError> {
+        loop {
+            let descriptor_type = match self.remaining().first() {
+                Some(descriptor_type) => *descriptor_type,
+                None => return Ok(None),
+            };
+            let start = self.offset;
+            let (host, port) = match descriptor_type {
+                TYPE_IPV4 => {
+                    let payload = self.take(descriptor_type, 4 + 2)?;
+                    let mut ip = [0u8; 4];
+                    ip.copy_from_slice(&payload[..4]);
+                    (Host(HostInner::Ip(net::Ipv4Addr::from(ip).into())), &payload[4..])
+                },
+                TYPE_IPV6 => {
+                    let payload = self.take(descriptor_type, 16 + 2)?;
+                    let mut ip = [0u8; 16];
+                    ip.copy_from_slice(&payload[..16]);
+                    (Host(HostInner::Ip(net::Ipv6Addr::from(ip).into())), &payload[16..])
+                },
+                TYPE_TOR_V2 => {
+                    self.take(descriptor_type, TOR_V2_LEN)?;
+                    continue;
+                },
+                TYPE_TOR_V3 => {
+                    let payload = self.take(descriptor_type, 35 + 2)?;
+                    let mut pubkey = [0u8; 32];
+                    pubkey.copy_from_slice(&payload[..32]);
+                    let version = payload[34];
+                    if version != ONION_V3_VERSION {
+                        self.offset = start;
+                        return Err(self.error(DecodeErrorInner::InvalidOnion(OnionError::UnsupportedVersion(version))));
+                    }
+                    if payload[32..34] != super::onion_v3_checksum(&pubkey) {
+                        self.offset = start;
+                        return Err(self.error(DecodeErrorInner::InvalidOnion(OnionError::InvalidChecksum)));
+                    }
+                    (Host(HostInner::OnionV3(pubkey)), &payload[35..])
+                },
+                TYPE_DNS => {
+                    let len = match self.bytes.get(self.offset + 1) {
+                        Some(len) => usize::from(*len),
+                        None => return Err(self.error(DecodeErrorInner::Truncated { descriptor_type, expected: 2, available: 1 })),
+                    };
+                    let payload = self.take(descriptor_type, 1 + len + 2)?;
+                    let hostname = &payload[1..(1 + len)];
+                    let host = decode_hostname(hostname).map_err(|reason| {
+                        self.offset = start;
+                        self.error(reason)
+                    })?;
+                    (host, &payload[(1 + len)..])
+                },
+                _ => return Ok(None),
+            };
+            let port = u16::from_be_bytes([port[0], port[1]]);
+            return Ok(Some(HostPort(host, port)));
+        }
+    }
+}
+
+fn decode_hostname(hostname: &[u8]) -> Result<Host, DecodeErrorInner> {
+    let hostname = core::str::from_utf8(hostname)
+        .ok()
+        .filter(|hostname| hostname.is_ascii())
+        .ok_or(DecodeErrorInner::InvalidHostname(HostnameError::NotAscii))?;
+    // IP and onion addresses have their own descriptor types
+    super::validate_pure_hostname(hostname).map_err(DecodeErrorInner::InvalidHostname)?;
+    Ok(Host(HostInner::Hostname(Hostname::from_validated(hostname))))
+}
+
+impl<'a> Iterator for Decode<'a> {
+    type Item = Result<HostPort<Host>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        // Unknown type is not consumed so the iterator keeps returning `None`
+        let result = self.decode_next().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+impl<'a> core::iter::FusedIterator for Decode<'a> {}
+
+/// Error returned when decoding of address descriptor fails.
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    offset: usize,
+    reason: DecodeErrorInner,
+}
+
+impl DecodeError {
+    /// Returns the offset of the descriptor which failed to decode.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns true if the error was caused by input ending in the middle of a descriptor.
+    pub fn is_truncated(&self) -> bool {
+        matches!(self.reason, DecodeErrorInner::Truncated { .. })
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to decode address descriptor at offset {}", self.offset; &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum DecodeErrorInner {
+    Truncated { descriptor_type: u8, expected: usize, available: usize },
+    InvalidOnion(OnionError),
+    InvalidHostname(HostnameError),
+}
+
+fn type_name(descriptor_type: u8) -> &'static str {
+    match descriptor_type {
+        TYPE_IPV4 => "IPv4",
+        TYPE_IPV6 => "IPv6",
+        TYPE_TOR_V2 => "Tor v2",
+        TYPE_TOR_V3 => "Tor v3",
+        TYPE_DNS => "DNS hostname",
+        _ => "unknown",
+    }
+}
+
+impl fmt::Display for DecodeErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeErrorInner::Truncated { descriptor_type, expected, available } => write!(f, "truncated {} descriptor: expected {} bytes, only {} available", type_name(*descriptor_type), expected, available),
+            DecodeErrorInner::InvalidOnion(error) => write_err!(f, "invalid onion address"; error),
+            DecodeErrorInner::InvalidHostname(error) => write_err!(f, "invalid hostname"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeErrorInner::Truncated { .. } => None,
+            DecodeErrorInner::InvalidOnion(error) => Some(error),
+            DecodeErrorInner::InvalidHostname(error) => Some(error),
+        }
+    }
+}
+
+/// Error returned when a hostname is too long to be encoded in address descriptor.
+///
+/// **Important: consumer code MUST NOT match on this using `EncodeError { .. }` syntax.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct EncodeError {
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the hostname is {} bytes long but at most 255 bytes can be encoded in address descriptor", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use slog::{Key, Value, Record, Serializer};
+
+    impl_error_value!(super::DecodeError);
+    #[cfg(feature = "alloc")]
+    impl_error_value!(super::EncodeError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use alloc::{vec, string::ToString};
+    #[cfg(feature = "alloc")]
+    use crate::p2p_address::{Host, HostPort};
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+
+    #[cfg(feature = "alloc")]
+    fn decode_all(bytes: &[u8]) -> Vec<HostPort<Host>> {
+        decode(bytes).collect::<Result<Vec<_>, _>>().unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn ipv4() {
+        let bytes = [1, 192, 168, 0, 1, 0x26, 0x07];
+        let decoded = decode_all(&bytes);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].to_string(), "192.168.0.1:9735");
+        assert_eq!(decoded[0].descriptor_len(), bytes.len());
+        let mut encoded = Vec::new();
+        decoded[0].encode_descriptor(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn ipv6() {
+        let mut bytes = vec![2];
+        bytes.extend_from_slice(&[0; 15]);
+        bytes.push(1);
+        bytes.extend_from_slice(&[0x04, 0xd2]);
+        let decoded = decode_all(&bytes);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].to_string(), "[::1]:1234");
+        let mut encoded = Vec::new();
+        decoded[0].encode_descriptor(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn onion_v3() {
+        let onion = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:9735";
+        let p2p = "022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion"
+            .parse::<crate::P2PAddress>()
+            .unwrap();
+        let mut encoded = Vec::new();
+        p2p.as_host_port().encode_descriptor(&mut encoded).unwrap();
+        assert_eq!(encoded.len(), 1 + 35 + 2);
+        assert_eq!(encoded[0], 4);
+        assert_eq!(encoded[35], 3);
+        let decoded = decode_all(&encoded);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].to_string(), onion);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn hostname() {
+        let mut bytes = vec![5, 11];
+        bytes.extend_from_slice(b"example.com");
+        bytes.extend_from_slice(&[0x26, 0x07]);
+        let decoded = decode_all(&bytes);
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].to_string(), "example.com:9735");
+        let mut encoded = Vec::new();
+        decoded[0].encode_descriptor(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+    }
+
+    #[test]
+    fn list_skips_tor_v2_and_stops_at_unknown() {
+        let mut bytes = vec![1, 127, 0, 0, 1, 0x26, 0x07];
+        bytes.push(3);
+        bytes.extend_from_slice(&[0; 12]);
+        bytes.extend_from_slice(&[1, 10, 0, 0, 1, 0x04, 0xd2]);
+        bytes.extend_from_slice(&[42, 1, 2, 3]);
+        let mut iter = decode(&bytes);
+        assert_eq!(iter.next().unwrap().unwrap().to_string(), "127.0.0.1:9735");
+        assert_eq!(iter.next().unwrap().unwrap().to_string(), "10.0.0.1:1234");
+        assert!(iter.next().is_none());
+        assert_eq!(iter.remaining(), [42, 1, 2, 3]);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn empty() {
+        assert!(decode(&[]).next().is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = [1, 127, 0, 0, 1, 0x26, 0x07, 2, 0, 0];
+        let mut iter = decode(&bytes);
+        assert!(iter.next().unwrap().is_ok());
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(error.is_truncated());
+        assert_eq!(error.offset(), 7);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.remaining(), [2, 0, 0]);
+    }
+
+    #[test]
+    fn truncated_hostname_len() {
+        let error = decode(&[5]).next().unwrap().unwrap_err();
+        assert!(error.is_truncated());
+        assert_eq!(error.offset(), 0);
+    }
+
+    #[test]
+    fn invalid_onion_checksum() {
+        let mut bytes = vec![4];
+        bytes.extend_from_slice(&[0; 32]);
+        bytes.extend_from_slice(&[0, 0, 3, 0x26, 0x07]);
+        let error = decode(&bytes).next().unwrap().unwrap_err();
+        assert!(!error.is_truncated());
+    }
+
+    #[test]
+    fn invalid_hostname() {
+        let mut bytes = vec![5, 9];
+        bytes.extend_from_slice(b"127.0.0.1");
+        bytes.extend_from_slice(&[0x26, 0x07]);
+        assert!(decode(&bytes).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn truncated_message() {
+        let error = decode(&[1, 10, 0, 2, 0, 0, 0, 2, 0, 0]).nth(1).unwrap().unwrap_err();
+        #[cfg(feature = "std")]
+        {
+            use std::error::Error;
+
+            assert_eq!(error.to_string(), "failed to decode address descriptor at offset 7");
+            assert_eq!(error.source().unwrap().to_string(), "truncated IPv6 descriptor: expected 19 bytes, only 3 available");
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            assert_eq!(error.to_string(), "failed to decode address descriptor at offset 7: truncated IPv6 descriptor: expected 19 bytes, only 3 available");
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Classification of addresses and choosing which ones to connect to
+//!
+//! Nodes may advertise addresses that are not reachable from the public internet - e.g. private
+//! ranges or addresses reserved for documentation. This module provides [`NetworkKind`] to
+//! classify a [`Host`] and [`ReachabilityPolicy`] to filter and order lists of addresses
+//! according to preference between clearnet and Tor.
+//!
+//! ## Example
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use ln_types::P2PAddress;
+//! use ln_types::p2p_address::ReachabilityPolicy;
+//!
+//! let mut addresses = [
+//!     "022345678901234567890123456789012345678901234567890123456789abcdef@192.168.1.1",
+//!     "022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion",
+//!     "022345678901234567890123456789012345678901234567890123456789abcdef@1.1.1.1",
+//! ].iter().map(|address| address.parse::<P2PAddress>().unwrap()).collect::<Vec<_>>();
+//!
+//! ReachabilityPolicy::tor_first().apply(&mut addresses);
+//! assert_eq!(addresses.len(), 2);
+//! assert!(addresses[0].host.is_onion());
+//! assert_eq!(addresses[1].host.to_string(), "1.1.1.1");
+//! # }
+//! ```
+
+use super::{Host, HostInner, net};
+#[cfg(feature = "alloc")]
+use super::P2PAddress;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Kind of network an address belongs to.
+///
+/// This is returned by [`Host::network_kind`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum NetworkKind {
+    /// Publicly routable IPv4 address
+    Ipv4Public,
+    /// Private IPv4 address (`10.0.0.0/8`, `172.16.0.0/12`, `192.168.0.0/16`)
+    Ipv4Private,
+    /// IPv4 loopback address (`127.0.0.0/8`)
+    Ipv4Loopback,
+    /// IPv4 link-local address (`169.254.0.0/16`)
+    Ipv4LinkLocal,
+    /// IPv4 shared address space used by carrier-grade NAT (`100.64.0.0/10`)
+    Ipv4Cgnat,
+    /// IPv4 address reserved for documentation (`192.0.2.0/24`, `198.51.100.0/24`,
+    /// `203.0.113.0/24`)
+    Ipv4Documentation,
+    /// Other IPv4 address that can not be connected to - unspecified, broadcast, multicast,
+    /// benchmarking or reserved
+    Ipv4Reserved,
+    /// Publicly routable IPv6 address
+    Ipv6Public,
+    /// IPv6 unique local address (`fc00::/7`)
+    Ipv6UniqueLocal,
+    /// IPv6 loopback address (`::1`)
+    Ipv6Loopback,
+    /// IPv6 link-local address (`fe80::/10`)
+    Ipv6LinkLocal,
+    /// IPv6 address reserved for documentation (`2001:db8::/32`)
+    Ipv6Documentation,
+    /// Other IPv6 address that can not be connected to - unspecified or multicast
+    Ipv6Reserved,
+    /// Tor onion service
+    Onion,
+    /// DNS hostname (not resolved)
+    Dns,
+}
+
+impl NetworkKind {
+    /// Classifies an IPv4 address.
+    pub fn from_ipv4(ip: net::Ipv4Addr) -> Self {
+        match ip.octets() {
+            [127, ..] => NetworkKind::Ipv4Loopback,
+            [10, ..] | [192, 168, ..] => NetworkKind::Ipv4Private,
+            [172, b, ..] if b & 0xf0 == 16 => NetworkKind::Ipv4Private,
+            [169, 254, ..] => NetworkKind::Ipv4LinkLocal,
+            [100, b, ..] if b & 0xc0 == 64 => NetworkKind::Ipv4Cgnat,
+            [192, 0, 2, _] | [198, 51, 100, _] | [203, 0, 113, _] => NetworkKind::Ipv4Documentation,
+            // "this network", IETF protocol assignments, benchmarking, multicast and reserved
+            // (including broadcast)
+            [0, ..] | [192, 0, 0, _] => NetworkKind::Ipv4Reserved,
+            [198, b, ..] if b & 0xfe == 18 => NetworkKind::Ipv4Reserved,
+            [a, ..] if a >= 224 => NetworkKind::Ipv4Reserved,
+            _ => NetworkKind::Ipv4Public,
+        }
+    }
+
+    /// Classifies an IPv6 address.
+    ///
+    /// IPv4-mapped addresses (`::ffff:0:0/96`) are classified as the IPv4 address they contain.
+    pub fn from_ipv6(ip: net::Ipv6Addr) -> Self {
+        let segments = ip.segments();
+        match segments {
+            [0, 0, 0, 0, 0, 0, 0, 1] => NetworkKind::Ipv6Loopback,
+            [0, 0, 0, 0, 0, 0, 0, 0] => NetworkKind::Ipv6Reserved,
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => {
+                let [a, b] = hi.to_be_bytes();
+                let [c, d] = lo.to_be_bytes();
+                NetworkKind::from_ipv4(net::Ipv4Addr::new(a, b, c, d))
+            },
+            [first, ..] if first & 0xfe00 == 0xfc00 => NetworkKind::Ipv6UniqueLocal,
+            [first, ..] if first & 0xffc0 == 0xfe80 => NetworkKind::Ipv6LinkLocal,
+            [first, ..] if first & 0xff00 == 0xff00 => NetworkKind::Ipv6Reserved,
+            [0x2001, 0x0db8, ..] => NetworkKind::Ipv6Documentation,
+            _ => NetworkKind::Ipv6Public,
+        }
+    }
+
+    /// Classifies an IP address.
+    pub fn from_ip(ip: net::IpAddr) -> Self {
+        match ip {
+            net::IpAddr::V4(ip) => Self::from_ipv4(ip),
+            net::IpAddr::V6(ip) => Self::from_ipv6(ip),
+        }
+    }
+
+    /// Returns true if the address can be reached from the public internet.
+    ///
+    /// Onion services are considered reachable, DNS hostnames too since they can not be
+    /// classified without resolving.
+    pub fn is_publicly_routable(self) -> bool {
+        matches!(self, NetworkKind::Ipv4Public | NetworkKind::Ipv6Public | NetworkKind::Onion | NetworkKind::Dns)
+    }
+
+    /// Returns true if the address is only reachable on the local network or machine.
+    ///
+    /// These addresses are useful in testing environments such as regtest.
+    pub fn is_local(self) -> bool {
+        matches!(self,
+            NetworkKind::Ipv4Private |
+            NetworkKind::Ipv4Loopback |
+            NetworkKind::Ipv4LinkLocal |
+            NetworkKind::Ipv4Cgnat |
+            NetworkKind::Ipv6UniqueLocal |
+            NetworkKind::Ipv6Loopback |
+            NetworkKind::Ipv6LinkLocal
+        )
+    }
+
+    /// Returns true if the address is not an onion address.
+    pub fn is_clearnet(self) -> bool {
+        self != NetworkKind::Onion
+    }
+}
+
+impl Host {
+    /// Classifies the network this host belongs to.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::p2p_address::{Host, HostPort, NetworkKind};
+    ///
+    /// let host_port = "100.64.0.1:9735".parse::<HostPort<Host>>().unwrap();
+    /// assert_eq!(host_port.0.network_kind(), NetworkKind::Ipv4Cgnat);
+    /// ```
+    pub fn network_kind(&self) -> NetworkKind {
+        match &self.0 {
+            HostInner::Ip(ip) => NetworkKind::from_ip(*ip),
+            HostInner::Ipv6Scoped(ip, _) => NetworkKind::from_ipv6(*ip),
+            HostInner::Hostname(_) => NetworkKind::Dns,
+            HostInner::OnionV3(_) => NetworkKind::Onion,
+        }
+    }
+}
+
+/// Preference between clearnet and Tor
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Preference {
+    ClearnetFirst,
+    ClearnetOnly,
+    TorFirst,
+    TorOnly,
+}
+
+/// Policy choosing which addresses to connect to and in which order.
+///
+/// Regardless of preference, addresses that are never reachable (documentation and reserved
+/// ranges) are always rejected. Local addresses (private, loopback, link-local and CGNAT) are
+/// rejected unless allowed using [`allow_local`](Self::allow_local).
+///
+/// "Tor" in this context means onion addresses only. Clearnet addresses may still be reached over
+/// Tor by connecting through its SOCKS5 proxy but that is up to the caller.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ReachabilityPolicy {
+    preference: Preference,
+    allow_local: bool,
+}
+
+impl ReachabilityPolicy {
+    /// Accepts all public addresses, preferring clearnet over onion addresses.
+    pub fn clearnet_first() -> Self {
+        Self::new(Preference::ClearnetFirst)
+    }
+
+    /// Accepts public clearnet addresses only.
+    pub fn clearnet_only() -> Self {
+        Self::new(Preference::ClearnetOnly)
+    }
+
+    /// Accepts all public addresses, preferring onion addresses over clearnet.
+    pub fn tor_first() -> Self {
+        Self::new(Preference::TorFirst)
+    }
+
+    /// Accepts onion addresses only.
+    pub fn tor_only() -> Self {
+        Self::new(Preference::TorOnly)
+    }
+
+    fn new(preference: Preference) -> Self {
+        ReachabilityPolicy {
+            preference,
+            allow_local: false,
+        }
+    }
+
+    /// Sets whether local addresses (e.g. `127.0.0.1` or `192.168.1.1`) are accepted.
+    ///
+    /// This is useful in testing environments such as regtest. Local addresses are never
+    /// accepted by Tor-only policy.
+    pub fn allow_local(mut self, allow: bool) -> Self {
+        self.allow_local = allow;
+        self
+    }
+
+    /// Returns the rank of the host - lower is better, `None` if not accepted.
+    fn rank(&self, host: &Host) -> Option<u8> {
+        let kind = host.network_kind();
+        if !(kind.is_publicly_routable() || (self.allow_local && kind.is_local())) {
+            return None;
+        }
+        match (self.preference, kind.is_clearnet()) {
+            (Preference::ClearnetFirst, true) | (Preference::ClearnetOnly, true) => Some(0),
+            (Preference::ClearnetFirst, false) => Some(1),
+            (Preference::TorFirst, false) | (Preference::TorOnly, false) => Some(0),
+            (Preference::TorFirst, true) => Some(1),
+            (Preference::ClearnetOnly, false) | (Preference::TorOnly, true) => None,
+        }
+    }
+
+    /// Returns true if the policy accepts the host.
+    pub fn accepts(&self, host: &Host) -> bool {
+        self.rank(host).is_some()
+    }
+
+    /// Sorts the addresses by preference.
+    ///
+    /// The sort is stable so the addresses of the same preference keep their order. Addresses
+    /// that are not accepted are moved to the end.
+    #[cfg(feature = "alloc")]
+    pub fn sort(&self, addresses: &mut [P2PAddress]) {
+        addresses.sort_by_key(|address| self.rank(&address.host).unwrap_or(u8::MAX));
+    }
+
+    /// Removes addresses that are not accepted and sorts the remaining ones by preference.
+    #[cfg(feature = "alloc")]
+    pub fn apply(&self, addresses: &mut Vec<P2PAddress>) {
+        addresses.retain(|address| self.accepts(&address.host));
+        self.sort(addresses);
+    }
+
+    /// Returns an iterator over accepted addresses in the order of preference.
+    ///
+    /// Unlike [`apply`](Self::apply) this doesn't modify the input.
+    #[cfg(feature = "alloc")]
+    pub fn select<'a, I: IntoIterator<Item=&'a P2PAddress>>(&self, addresses: I) -> impl Iterator<Item=&'a P2PAddress> {
+        let policy = *self;
+        let mut selected = addresses
+            .into_iter()
+            .filter_map(|address| Some((policy.rank(&address.host)?, address)))
+            .collect::<Vec<_>>();
+        selected.sort_by_key(|(rank, _)| *rank);
+        selected.into_iter().map(|(_, address)| address)
+    }
+}
+
+/// Accepts clearnet and onion addresses, preferring clearnet.
+impl Default for ReachabilityPolicy {
+    fn default() -> Self {
+        Self::clearnet_first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NetworkKind, ReachabilityPolicy};
+    use crate::p2p_address::{Host, HostPort};
+    #[cfg(feature = "alloc")]
+    use crate::P2PAddress;
+    #[cfg(feature = "alloc")]
+    use alloc::{format, vec::Vec, string::ToString};
+
+    const ONION: &str = "duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion";
+
+    fn kind(host: &str) -> NetworkKind {
+        host.parse::<HostPort<Host>>().unwrap().0.network_kind()
+    }
+
+    #[test]
+    fn ipv4_kinds() {
+        assert_eq!(kind("1.1.1.1"), NetworkKind::Ipv4Public);
+        assert_eq!(kind("10.1.2.3"), NetworkKind::Ipv4Private);
+        assert_eq!(kind("172.16.0.1"), NetworkKind::Ipv4Private);
+        assert_eq!(kind("172.31.255.255"), NetworkKind::Ipv4Private);
+        assert_eq!(kind("172.32.0.1"), NetworkKind::Ipv4Public);
+        assert_eq!(kind("192.168.1.1"), NetworkKind::Ipv4Private);
+        assert_eq!(kind("127.0.0.1"), NetworkKind::Ipv4Loopback);
+        assert_eq!(kind("169.254.1.1"), NetworkKind::Ipv4LinkLocal);
+        assert_eq!(kind("100.64.0.1"), NetworkKind::Ipv4Cgnat);
+        assert_eq!(kind("100.127.255.255"), NetworkKind::Ipv4Cgnat);
+        assert_eq!(kind("100.128.0.1"), NetworkKind::Ipv4Public);
+        assert_eq!(kind("192.0.2.1"), NetworkKind::Ipv4Documentation);
+        assert_eq!(kind("198.51.100.1"), NetworkKind::Ipv4Documentation);
+        assert_eq!(kind("203.0.113.1"), NetworkKind::Ipv4Documentation);
+        assert_eq!(kind("0.0.0.0"), NetworkKind::Ipv4Reserved);
+        assert_eq!(kind("198.18.0.1"), NetworkKind::Ipv4Reserved);
+        assert_eq!(kind("224.0.0.1"), NetworkKind::Ipv4Reserved);
+        assert_eq!(kind("255.255.255.255"), NetworkKind::Ipv4Reserved);
+    }
+
+    #[test]
+    fn ipv6_kinds() {
+        assert_eq!(kind("[2606:4700::1111]"), NetworkKind::Ipv6Public);
+        assert_eq!(kind("[fd00::1]"), NetworkKind::Ipv6UniqueLocal);
+        assert_eq!(kind("[::1]"), NetworkKind::Ipv6Loopback);
+        assert_eq!(kind("[fe80::1]"), NetworkKind::Ipv6LinkLocal);
+        assert_eq!(kind("[2001:db8::1]"), NetworkKind::Ipv6Documentation);
+        assert_eq!(kind("[::]"), NetworkKind::Ipv6Reserved);
+        assert_eq!(kind("[ff02::1]"), NetworkKind::Ipv6Reserved);
+        assert_eq!(kind("[::ffff:192.168.1.1]"), NetworkKind::Ipv4Private);
+    }
+
+    #[test]
+    fn other_kinds() {
+        assert_eq!(kind(ONION), NetworkKind::Onion);
+        #[cfg(feature = "alloc")]
+        assert_eq!(kind("example.com"), NetworkKind::Dns);
+    }
+
+    #[test]
+    fn accepts() {
+        let host = |host: &str| host.parse::<HostPort<Host>>().unwrap().0;
+        assert!(ReachabilityPolicy::default().accepts(&host("1.1.1.1")));
+        assert!(ReachabilityPolicy::default().accepts(&host(ONION)));
+        assert!(!ReachabilityPolicy::default().accepts(&host("127.0.0.1")));
+        assert!(ReachabilityPolicy::default().allow_local(true).accepts(&host("127.0.0.1")));
+        assert!(!ReachabilityPolicy::default().allow_local(true).accepts(&host("192.0.2.1")));
+        assert!(!ReachabilityPolicy::clearnet_only().accepts(&host(ONION)));
+        assert!(!ReachabilityPolicy::tor_only().accepts(&host("1.1.1.1")));
+    }
+
+    #[cfg(feature = "alloc")]
+    fn addresses(hosts: &[&str]) -> Vec<P2PAddress> {
+        hosts
+            .iter()
+            .map(|host| format!("022345678901234567890123456789012345678901234567890123456789abcdef@{}", host).parse().unwrap())
+            .collect()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn hosts(addresses: &[P2PAddress]) -> Vec<alloc::string::String> {
+        addresses.iter().map(|address| address.host.to_string()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sort_keeps_order_and_moves_rejected_to_end() {
+        let mut list = addresses(&["127.0.0.1", ONION, "1.1.1.1", "[2606:4700::1111]"]);
+        ReachabilityPolicy::tor_first().sort(&mut list);
+        assert_eq!(hosts(&list), [ONION, "1.1.1.1", "2606:4700::1111", "127.0.0.1"]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn apply() {
+        let all = addresses(&["127.0.0.1", ONION, "192.0.2.1", "1.1.1.1", "example.com", "10.0.0.1"]);
+
+        let mut list = all.clone();
+        ReachabilityPolicy::clearnet_first().apply(&mut list);
+        assert_eq!(hosts(&list), ["1.1.1.1", "example.com", ONION]);
+
+        let mut list = all.clone();
+        ReachabilityPolicy::clearnet_only().allow_local(true).apply(&mut list);
+        assert_eq!(hosts(&list), ["127.0.0.1", "1.1.1.1", "example.com", "10.0.0.1"]);
+
+        let mut list = all.clone();
+        ReachabilityPolicy::tor_only().allow_local(true).apply(&mut list);
+        assert_eq!(hosts(&list), [ONION]);
+
+        let selected = ReachabilityPolicy::tor_first().select(&all).map(|address| address.host.to_string()).collect::<Vec<_>>();
+        assert_eq!(selected, [ONION, "1.1.1.1", "example.com"]);
+    }
+}
@@ -0,0 +1,497 @@
+//! Connecting through SOCKS5 proxy
+//!
+//! Onion addresses can not be resolved so the only way to reach them is through a proxy - usually
+//! Tor. This module implements the client side of SOCKS5 ([RFC 1928]) `CONNECT` command including
+//! username/password authentication ([RFC 1929]) which Tor uses for stream isolation.
+//!
+//! Hostnames (including onion addresses) are sent to the proxy unresolved (address type 3) so
+//! that the proxy performs the resolution. This also prevents leaking DNS requests.
+//!
+//! [RFC 1928]: https://datatracker.ietf.org/doc/html/rfc1928
+//! [RFC 1929]: https://datatracker.ietf.org/doc/html/rfc1929
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use ln_types::p2p_address::socks5::Credentials;
+//!
+//! let address = "029ef8ee0ba895e2807ac1df1987a7888116c468e70f42e7b089e06811b0e45482@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion"
+//!     .parse::<ln_types::P2PAddress>()
+//!     .unwrap();
+//! let proxy = "127.0.0.1:9050".parse().unwrap();
+//! // Connections using different credentials go through different Tor circuits
+//! let credentials = Credentials { username: "channel-1", password: "channel-1" };
+//! let stream = address.connect_via_socks5(proxy, Some(credentials)).unwrap();
+//! ```
+
+use core::borrow::Borrow;
+use core::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use super::{Host, HostInner, HostPort, P2PAddress, net};
+
+const SOCKS_VERSION: u8 = 5;
+const AUTH_VERSION: u8 = 1;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+const METHOD_NO_ACCEPTABLE: u8 = 0xff;
+const COMMAND_CONNECT: u8 = 1;
+const ATYP_IPV4: u8 = 1;
+const ATYP_DOMAIN: u8 = 3;
+const ATYP_IPV6: u8 = 4;
+
+/// Maximum length of `CONNECT` request
+const MAX_REQUEST_LEN: usize = 4 + 1 + 255 + 2;
+/// Maximum length of username/password authentication request
+const MAX_AUTH_LEN: usize = 1 + 1 + 255 + 1 + 255;
+/// Length of the reply prefix that determines the length of the rest
+const REPLY_HEADER_LEN: usize = 5;
+
+/// Username and password sent to the proxy.
+///
+/// Tor does not verify these but uses them for stream isolation: connections with different
+/// credentials are never sent over the same circuit. Both fields can be at most 255 bytes long.
+#[derive(Debug, Copy, Clone)]
+pub struct Credentials<'a> {
+    /// The user name
+    pub username: &'a str,
+    /// The password
+    pub password: &'a str,
+}
+
+/// Connects to `target` through SOCKS5 proxy at `proxy`.
+///
+/// The returned stream is connected to the target - anything written to it is forwarded by the
+/// proxy. Errors reported by the proxy are returned as [`io::Error`] containing [`Error`].
+pub fn connect<H: Borrow<Host>>(proxy: SocketAddr, target: &HostPort<H>, credentials: Option<Credentials<'_>>) -> io::Result<TcpStream> {
+    let mut request_buf = [0; MAX_REQUEST_LEN];
+    let request = connect_request(target, &mut request_buf)?;
+    let mut auth_buf = [0; MAX_AUTH_LEN];
+    let auth = credentials.map(|credentials| auth_request(credentials, &mut auth_buf)).transpose()?;
+
+    let mut stream = TcpStream::connect(proxy)?;
+    stream.write_all(&greeting(auth.is_some()))?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply)?;
+    check_method(reply, auth.is_some())?;
+    if let Some(auth) = auth {
+        stream.write_all(auth)?;
+        stream.read_exact(&mut reply)?;
+        check_auth(reply)?;
+    }
+    stream.write_all(request)?;
+    let mut header = [0; REPLY_HEADER_LEN];
+    stream.read_exact(&mut header)?;
+    let mut bound_address = [0; 257];
+    stream.read_exact(&mut bound_address[..check_reply(header)?])?;
+    Ok(stream)
+}
+
+/// Connects to `target` through SOCKS5 proxy at `proxy` asynchronously.
+///
+/// This is the async version of [`connect`].
+#[cfg(feature = "tokio")]
+pub async fn connect_async<H: Borrow<Host>>(proxy: SocketAddr, target: &HostPort<H>, credentials: Option<Credentials<'_>>) -> io::Result<tokio::net::TcpStream> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut request_buf = [0; MAX_REQUEST_LEN];
+    let request = connect_request(target, &mut request_buf)?;
+    let mut auth_buf = [0; MAX_AUTH_LEN];
+    let auth = credentials.map(|credentials| auth_request(credentials, &mut auth_buf)).transpose()?;
+
+    let mut stream = tokio::net::TcpStream::connect(proxy).await?;
+    stream.write_all(&greeting(auth.is_some())).await?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).await?;
+    check_method(reply, auth.is_some())?;
+    if let Some(auth) = auth {
+        stream.write_all(auth).await?;
+        stream.read_exact(&mut reply).await?;
+        check_auth(reply)?;
+    }
+    stream.write_all(request).await?;
+    let mut header = [0; REPLY_HEADER_LEN];
+    stream.read_exact(&mut header).await?;
+    let mut bound_address = [0; 257];
+    stream.read_exact(&mut bound_address[..check_reply(header)?]).await?;
+    Ok(stream)
+}
+
+impl P2PAddress {
+    /// Connects to the node through SOCKS5 proxy (e.g. Tor).
+    ///
+    /// This is the only way to connect to onion addresses. See the [`socks5`](self) module for
+    /// details.
+    pub fn connect_via_socks5(&self, proxy: SocketAddr, credentials: Option<Credentials<'_>>) -> io::Result<TcpStream> {
+        connect(proxy, &self.as_host_port(), credentials)
+    }
+
+    /// Connects to the node through SOCKS5 proxy (e.g. Tor) asynchronously.
+    ///
+    /// This is the async version of [`connect_via_socks5`](Self::connect_via_socks5).
+    #[cfg(feature = "tokio")]
+    pub async fn connect_via_socks5_async(&self, proxy: SocketAddr, credentials: Option<Credentials<'_>>) -> io::Result<tokio::net::TcpStream> {
+        connect_async(proxy, &self.as_host_port(), credentials).await
+    }
+}
+
+/// Offers exactly one authentication method - the one we can use.
+fn greeting(use_auth: bool) -> [u8; 3] {
+    let method = if use_auth { METHOD_USERNAME_PASSWORD } else { METHOD_NO_AUTH };
+    [SOCKS_VERSION, 1, method]
+}
+
+fn check_method(reply: [u8; 2], use_auth: bool) -> Result<(), Error> {
+    if reply[0] != SOCKS_VERSION {
+        return Err(Error(ErrorInner::InvalidVersion(reply[0])));
+    }
+    match (reply[1], use_auth) {
+        (METHOD_NO_AUTH, false) | (METHOD_USERNAME_PASSWORD, true) => Ok(()),
+        (METHOD_NO_ACCEPTABLE, _) => Err(Error(ErrorInner::NoAcceptableMethod)),
+        (method, _) => Err(Error(ErrorInner::UnexpectedMethod(method))),
+    }
+}
+
+fn auth_request<'a>(credentials: Credentials<'_>, buf: &'a mut [u8; MAX_AUTH_LEN]) -> Result<&'a [u8], Error> {
+    let username = credentials.username.as_bytes();
+    let password = credentials.password.as_bytes();
+    if username.is_empty() || username.len() > 255 || password.len() > 255 {
+        return Err(Error(ErrorInner::InvalidCredentials));
+    }
+    buf[0] = AUTH_VERSION;
+    buf[1] = username.len() as u8;
+    buf[2..(2 + username.len())].copy_from_slice(username);
+    let pos = 2 + username.len();
+    buf[pos] = password.len() as u8;
+    buf[(pos + 1)..(pos + 1 + password.len())].copy_from_slice(password);
+    Ok(&buf[..(pos + 1 + password.len())])
+}
+
+fn check_auth(reply: [u8; 2]) -> Result<(), Error> {
+    if reply[0] != AUTH_VERSION {
+        return Err(Error(ErrorInner::InvalidVersion(reply[0])));
+    }
+    if reply[1] != 0 {
+        return Err(Error(ErrorInner::AuthFailed(reply[1])));
+    }
+    Ok(())
+}
+
+fn connect_request<'a, H: Borrow<Host>>(target: &HostPort<H>, buf: &'a mut [u8; MAX_REQUEST_LEN]) -> Result<&'a [u8], Error> {
+    buf[..3].copy_from_slice(&[SOCKS_VERSION, COMMAND_CONNECT, 0]);
+    let end = match &target.0.borrow().0 {
+        HostInner::Ip(net::IpAddr::V4(ip)) => {
+            buf[3] = ATYP_IPV4;
+            buf[4..8].copy_from_slice(&ip.octets());
+            8
+        },
+        HostInner::Ip(net::IpAddr::V6(ip)) | HostInner::Ipv6Scoped(ip, _) => {
+            buf[3] = ATYP_IPV6;
+            buf[4..20].copy_from_slice(&ip.octets());
+            20
+        },
+        HostInner::Hostname(hostname) => write_domain(buf, hostname.as_str().as_bytes())?,
+        HostInner::OnionV3(pubkey) => {
+            let mut onion = [0u8; super::ONION_V3_LEN + super::ONION_SUFFIX.len()];
+            super::encode_onion_v3(pubkey, &mut onion);
+            write_domain(buf, &onion)?
+        },
+    };
+    buf[end..(end + 2)].copy_from_slice(&target.1.to_be_bytes());
+    Ok(&buf[..(end + 2)])
+}
+
+/// Writes address type 3 followed by length-prefixed domain, returns the end position.
+fn write_domain(buf: &mut [u8; MAX_REQUEST_LEN], domain: &[u8]) -> Result<usize, Error> {
+    if domain.len() > 255 {
+        return Err(Error(ErrorInner::HostnameTooLong(domain.len())));
+    }
+    buf[3] = ATYP_DOMAIN;
+    buf[4] = domain.len() as u8;
+    buf[5..(5 + domain.len())].copy_from_slice(domain);
+    Ok(5 + domain.len())
+}
+
+/// Checks the reply header, returning the number of remaining bytes of the reply.
+fn check_reply(header: [u8; REPLY_HEADER_LEN]) -> Result<usize, Error> {
+    if header[0] != SOCKS_VERSION {
+        return Err(Error(ErrorInner::InvalidVersion(header[0])));
+    }
+    if header[1] != 0 {
+        return Err(Error(ErrorInner::ConnectFailed(header[1])));
+    }
+    // The header contains the first byte of address
+    match header[3] {
+        ATYP_IPV4 => Ok(4 - 1 + 2),
+        ATYP_IPV6 => Ok(16 - 1 + 2),
+        ATYP_DOMAIN => Ok(usize::from(header[4]) + 2),
+        atyp => Err(Error(ErrorInner::InvalidAddressType(atyp))),
+    }
+}
+
+/// Error returned when SOCKS5 handshake fails.
+///
+/// This is returned inside [`io::Error`] and can be obtained using [`io::Error::get_ref`] and
+/// downcasting.
+///
+/// **Important: consumer code MUST NOT match on this using `Error { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct Error(ErrorInner);
+
+impl Error {
+    /// Returns the reply code if the proxy refused to connect to the target.
+    ///
+    /// The meaning of the codes is defined in RFC 1928, Tor also defines its own extended codes.
+    pub fn reply_code(&self) -> Option<u8> {
+        match self.0 {
+            ErrorInner::ConnectFailed(code) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ErrorInner {
+    InvalidVersion(u8),
+    NoAcceptableMethod,
+    UnexpectedMethod(u8),
+    InvalidCredentials,
+    AuthFailed(u8),
+    HostnameTooLong(usize),
+    ConnectFailed(u8),
+    InvalidAddressType(u8),
+}
+
+fn reply_code_description(code: u8) -> &'static str {
+    match code {
+        1 => "general SOCKS server failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            ErrorInner::InvalidVersion(version) => write!(f, "the proxy responded with invalid version {}", version),
+            ErrorInner::NoAcceptableMethod => f.write_str("the proxy didn't accept any of the offered authentication methods"),
+            ErrorInner::UnexpectedMethod(method) => write!(f, "the proxy selected authentication method {} which was not offered", method),
+            ErrorInner::InvalidCredentials => f.write_str("the username must be 1 to 255 bytes long and the password at most 255 bytes long"),
+            ErrorInner::AuthFailed(status) => write!(f, "the proxy rejected the credentials (status {})", status),
+            ErrorInner::HostnameTooLong(len) => write!(f, "the hostname is {} bytes long but at most 255 bytes are supported", len),
+            ErrorInner::ConnectFailed(code) => write!(f, "the proxy failed to connect: {} (code {})", reply_code_description(code), code),
+            ErrorInner::InvalidAddressType(atyp) => write!(f, "the proxy responded with invalid address type {}", atyp),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(value: Error) -> Self {
+        let kind = match value.0 {
+            ErrorInner::InvalidCredentials | ErrorInner::HostnameTooLong(_) => io::ErrorKind::InvalidInput,
+            ErrorInner::AuthFailed(_) | ErrorInner::NoAcceptableMethod => io::ErrorKind::PermissionDenied,
+            ErrorInner::ConnectFailed(5) => io::ErrorKind::ConnectionRefused,
+            ErrorInner::ConnectFailed(2) => io::ErrorKind::PermissionDenied,
+            ErrorInner::InvalidVersion(_) | ErrorInner::UnexpectedMethod(_) | ErrorInner::InvalidAddressType(_) => io::ErrorKind::InvalidData,
+            ErrorInner::ConnectFailed(_) => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, value)
+    }
+}
+
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use slog::{Key, Value, Record, Serializer};
+
+    impl_error_value!(super::Error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Credentials;
+    use crate::P2PAddress;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, SocketAddr};
+    use std::thread;
+    use std::vec::Vec;
+    use alloc::string::{String, ToString};
+
+    const ONION_ADDRESS: &str = "022345678901234567890123456789012345678901234567890123456789abcdef@duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion:9735";
+
+    /// What the stand-in proxy received
+    #[derive(Debug, PartialEq, Eq)]
+    struct Received {
+        credentials: Option<(String, String)>,
+        atyp: u8,
+        address: Vec<u8>,
+        port: u16,
+    }
+
+    fn read_vec(stream: &mut impl Read, len: usize) -> Vec<u8> {
+        let mut buf = std::vec![0; len];
+        stream.read_exact(&mut buf).unwrap();
+        buf
+    }
+
+    /// Minimal SOCKS5 server that accepts one connection, replies with `reply_code` and writes
+    /// `hello` on success.
+    fn stand_in_proxy(reply_code: u8, accept_auth: bool) -> (SocketAddr, thread::JoinHandle<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let header = read_vec(&mut stream, 2);
+            assert_eq!(header[0], 5);
+            let methods = read_vec(&mut stream, header[1].into());
+            let credentials = if methods.contains(&2) {
+                stream.write_all(&[5, 2]).unwrap();
+                let version = read_vec(&mut stream, 2);
+                assert_eq!(version[0], 1);
+                let username = read_vec(&mut stream, version[1].into());
+                let password_len = read_vec(&mut stream, 1)[0];
+                let password = read_vec(&mut stream, password_len.into());
+                let credentials = Some((String::from_utf8(username).unwrap(), String::from_utf8(password).unwrap()));
+                if !accept_auth {
+                    stream.write_all(&[1, 1]).unwrap();
+                    return Received { credentials, atyp: 0, address: Vec::new(), port: 0 };
+                }
+                stream.write_all(&[1, 0]).unwrap();
+                credentials
+            } else {
+                stream.write_all(&[5, 0]).unwrap();
+                None
+            };
+            let request = read_vec(&mut stream, 4);
+            assert_eq!(request[..3], [5, 1, 0]);
+            let atyp = request[3];
+            let address = match atyp {
+                1 => read_vec(&mut stream, 4),
+                3 => {
+                    let len = read_vec(&mut stream, 1)[0];
+                    read_vec(&mut stream, len.into())
+                },
+                4 => read_vec(&mut stream, 16),
+                _ => panic!("invalid address type"),
+            };
+            let port = read_vec(&mut stream, 2);
+            let port = u16::from_be_bytes([port[0], port[1]]);
+            stream.write_all(&[5, reply_code, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+            if reply_code == 0 {
+                stream.write_all(b"hello").unwrap();
+            }
+            Received {
+                credentials,
+                atyp,
+                address,
+                port,
+            }
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn onion_with_credentials() {
+        let (proxy, handle) = stand_in_proxy(0, true);
+        let address = ONION_ADDRESS.parse::<P2PAddress>().unwrap();
+        let credentials = Credentials { username: "isolation", password: "secret" };
+        let mut stream = address.connect_via_socks5(proxy, Some(credentials)).unwrap();
+        let mut hello = [0; 5];
+        stream.read_exact(&mut hello).unwrap();
+        assert_eq!(&hello, b"hello");
+
+        let received = handle.join().unwrap();
+        assert_eq!(received, Received {
+            credentials: Some(("isolation".to_string(), "secret".to_string())),
+            atyp: 3,
+            address: b"duckduckgogg42xjoc72x3sjasowoarfbgcmvfimaftt6twagswzczad.onion".to_vec(),
+            port: 9735,
+        });
+    }
+
+    #[test]
+    fn hostname_without_credentials() {
+        let (proxy, handle) = stand_in_proxy(0, true);
+        let address = "022345678901234567890123456789012345678901234567890123456789abcdef@example.com:1234".parse::<P2PAddress>().unwrap();
+        address.connect_via_socks5(proxy, None).unwrap();
+
+        let received = handle.join().unwrap();
+        assert_eq!(received, Received {
+            credentials: None,
+            atyp: 3,
+            address: b"example.com".to_vec(),
+            port: 1234,
+        });
+    }
+
+    #[test]
+    fn ipv4() {
+        let (proxy, handle) = stand_in_proxy(0, true);
+        let address = "022345678901234567890123456789012345678901234567890123456789abcdef@10.0.0.1".parse::<P2PAddress>().unwrap();
+        address.connect_via_socks5(proxy, None).unwrap();
+
+        let received = handle.join().unwrap();
+        assert_eq!(received.atyp, 1);
+        assert_eq!(received.address, [10, 0, 0, 1]);
+    }
+
+    #[test]
+    fn connection_refused() {
+        let (proxy, handle) = stand_in_proxy(5, true);
+        let address = ONION_ADDRESS.parse::<P2PAddress>().unwrap();
+        let error = address.connect_via_socks5(proxy, None).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::ConnectionRefused);
+        let inner = error.get_ref().unwrap().downcast_ref::<super::Error>().unwrap();
+        assert_eq!(inner.reply_code(), Some(5));
+        assert_eq!(inner.to_string(), "the proxy failed to connect: connection refused (code 5)");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn auth_failed() {
+        let (proxy, handle) = stand_in_proxy(0, false);
+        let address = ONION_ADDRESS.parse::<P2PAddress>().unwrap();
+        let credentials = Credentials { username: "user", password: "wrong" };
+        let error = address.connect_via_socks5(proxy, Some(credentials)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+        let received = handle.join().unwrap();
+        assert_eq!(received.credentials, Some(("user".to_string(), "wrong".to_string())));
+    }
+
+    #[test]
+    fn invalid_credentials() {
+        // Validation happens before connecting so the proxy address doesn't matter
+        let proxy = "127.0.0.1:1".parse().unwrap();
+        let address = ONION_ADDRESS.parse::<P2PAddress>().unwrap();
+        let credentials = Credentials { username: "", password: "" };
+        let error = address.connect_via_socks5(proxy, Some(credentials)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn onion_async() {
+        use tokio::io::AsyncReadExt;
+
+        let (proxy, handle) = stand_in_proxy(0, true);
+        let address = ONION_ADDRESS.parse::<P2PAddress>().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+        runtime.block_on(async {
+            let credentials = Credentials { username: "isolation", password: "secret" };
+            let mut stream = address.connect_via_socks5_async(proxy, Some(credentials)).await.unwrap();
+            let mut hello = [0; 5];
+            stream.read_exact(&mut hello).await.unwrap();
+            assert_eq!(&hello, b"hello");
+        });
+        let received = handle.join().unwrap();
+        assert_eq!(received.atyp, 3);
+        assert_eq!(received.credentials, Some(("isolation".to_string(), "secret".to_string())));
+    }
+}
@@ -0,0 +1,275 @@
+//! Lightning URIs wrapping P2P addresses
+//!
+//! Wallets and QR codes often present node addresses as `lightning:node_id@host:port` or
+//! `lnconnect://node_id@host:port`. [`P2PAddress::parse_uri`] accepts these wrappers as well as
+//! plain addresses so whatever the user pasted can be handled by a single function.
+//!
+//! The scheme is case-insensitive, the address may be percent-encoded and query parameters or a
+//! fragment are ignored.
+//!
+//! ## Example
+//!
+//! ```
+//! use ln_types::P2PAddress;
+//!
+//! let address = P2PAddress::parse_uri("LIGHTNING:022345678901234567890123456789012345678901234567890123456789abcdef%40127.0.0.1%3A1234?label=foo").unwrap();
+//! assert_eq!(address.port, 1234);
+//! # #[cfg(feature = "alloc")]
+//! assert_eq!(address.to_uri(), "lightning:022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234");
+//! ```
+
+use core::fmt;
+use super::{Host, P2PAddress, ParseErrorInner as AddressParseErrorInner};
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, format, string::String};
+
+/// Schemes accepted by [`P2PAddress::parse_uri`], compared case-insensitively
+const SCHEMES: [&str; 2] = ["lightning", "lnconnect"];
+/// Maximum length of percent-decoded address: node ID, `@`, hostname, `:` and port
+const MAX_ADDRESS_LEN: usize = 66 + 1 + super::MAX_HOSTNAME_LEN + 1 + 5;
+
+impl P2PAddress {
+    /// Parses P2P address possibly wrapped in a Lightning URI.
+    ///
+    /// Accepts `lightning:`, `lightning://`, `lnconnect://` (case-insensitive) or no scheme at all.
+    /// The address is percent-decoded, query parameters and fragment are ignored, surrounding
+    /// whitespace is trimmed.
+    ///
+    /// This doesn't allocate even if the address is percent-encoded.
+    pub fn parse_uri(s: &str) -> Result<Self, ParseError> {
+        parse_uri_raw(s).map_err(|reason| ParseError {
+            #[cfg(feature = "alloc")]
+            input: s.to_owned(),
+            reason,
+        })
+    }
+
+    /// Returns the address formatted as a `lightning:` URI.
+    #[cfg(feature = "alloc")]
+    pub fn to_uri(&self) -> String {
+        format!("lightning:{}", self)
+    }
+}
+
+fn parse_uri_raw(s: &str) -> Result<P2PAddress, ParseErrorInner> {
+    let address = strip_scheme(s.trim())?;
+    let address = address
+        .split(|c| c == '?' || c == '#')
+        .next()
+        .expect("split always returns at least one item");
+    let address = address.strip_suffix('/').unwrap_or(address);
+
+    // Positions in errors are reported relative to the original input
+    let offset = address.as_ptr() as usize - s.as_ptr() as usize;
+    let mut buf = [0u8; MAX_ADDRESS_LEN];
+    let address = percent_decode(address, &mut buf)
+        .map_err(|pos| ParseErrorInner::InvalidPercentEncoding(offset + pos))?
+        .ok_or(ParseErrorInner::TooLong)?;
+    let address = core::str::from_utf8(address).map_err(|_| ParseErrorInner::NotUtf8)?;
+    let (node_id, host, port) = P2PAddress::parse_raw(address).map_err(ParseErrorInner::InvalidAddress)?;
+
+    Ok(P2PAddress {
+        node_id,
+        host: Host(host),
+        port,
+    })
+}
+
+/// Checks the syntax of URI scheme according to RFC 3986.
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.'),
+        _ => false,
+    }
+}
+
+/// Strips supported scheme, returns the input unchanged if there's none.
+fn strip_scheme(s: &str) -> Result<&str, ParseErrorInner> {
+    let colon = match s.find(':') {
+        Some(colon) => colon,
+        None => return Ok(s),
+    };
+    let scheme = &s[..colon];
+    // Node IDs may start with a letter but they are never followed by `:` - `@` comes first.
+    // Hostnames with dots followed by port are not schemes either, they fail later with a better
+    // error message.
+    if !is_scheme(scheme) || scheme.contains('.') {
+        return Ok(s);
+    }
+    if !SCHEMES.iter().any(|known| scheme.eq_ignore_ascii_case(known)) {
+        return Err(ParseErrorInner::UnsupportedScheme);
+    }
+    let rest = &s[(colon + 1)..];
+    Ok(rest.strip_prefix("//").unwrap_or(rest))
+}
+
+fn hex_digit(digit: u8) -> Option<u8> {
+    char::from(digit).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Percent-decodes `input` into `buf` returning the decoded bytes.
+///
+/// Returns position of invalid escape sequence on error or `None` if `buf` is too short.
+fn percent_decode<'a>(input: &str, buf: &'a mut [u8; MAX_ADDRESS_LEN]) -> Result<Option<&'a [u8]>, usize> {
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+    let mut len = 0;
+    while pos < bytes.len() {
+        let byte = if bytes[pos] == b'%' {
+            let byte = bytes.get((pos + 1)..(pos + 3))
+                .and_then(|hex| Some(hex_digit(hex[0])? << 4 | hex_digit(hex[1])?))
+                .ok_or(pos)?;
+            pos += 3;
+            byte
+        } else {
+            pos += 1;
+            bytes[pos - 1]
+        };
+        match buf.get_mut(len) {
+            Some(slot) => *slot = byte,
+            None => return Ok(None),
+        }
+        len += 1;
+    }
+    Ok(Some(&buf[..len]))
+}
+
+/// Error returned when parsing Lightning URI fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    #[cfg(feature = "alloc")]
+    input: String,
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        {
+            write_err!(f, "failed to parse '{}' as Lightning URI", self.input; &self.reason)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            write_err!(f, "failed to parse Lightning URI"; &self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    UnsupportedScheme,
+    InvalidPercentEncoding(usize),
+    NotUtf8,
+    TooLong,
+    InvalidAddress(AddressParseErrorInner),
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::UnsupportedScheme => f.write_str("unsupported scheme (only 'lightning' and 'lnconnect' are supported)"),
+            ParseErrorInner::InvalidPercentEncoding(pos) => write!(f, "invalid percent encoding at position {}", pos),
+            ParseErrorInner::NotUtf8 => f.write_str("the percent-decoded address is not valid UTF-8"),
+            ParseErrorInner::TooLong => write!(f, "the percent-decoded address is longer than {} bytes", MAX_ADDRESS_LEN),
+            ParseErrorInner::InvalidAddress(error) => write_err!(f, "invalid address"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseErrorInner::UnsupportedScheme => None,
+            ParseErrorInner::InvalidPercentEncoding(_) => None,
+            ParseErrorInner::NotUtf8 => None,
+            ParseErrorInner::TooLong => None,
+            ParseErrorInner::InvalidAddress(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use slog::{Key, Value, Record, Serializer};
+
+    impl_error_value!(super::ParseError);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::P2PAddress;
+    use alloc::string::ToString;
+
+    const ADDRESS: &str = "022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234";
+
+    fn check(input: &str) {
+        let address = P2PAddress::parse_uri(input).unwrap();
+        assert_eq!(address.to_string(), ADDRESS);
+    }
+
+    #[test]
+    fn plain() {
+        check(ADDRESS);
+        check("  022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234\n");
+    }
+
+    #[test]
+    fn schemes() {
+        check("lightning:022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234");
+        check("Lightning://022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234");
+        check("LNCONNECT://022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234/");
+    }
+
+    #[test]
+    fn percent_encoded_with_query() {
+        check("lightning:022345678901234567890123456789012345678901234567890123456789abcdef%40127.0.0.1%3a1234?label=My%20node&foo");
+        check("lnconnect://022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234#fragment");
+        let address = P2PAddress::parse_uri("lightning:022345678901234567890123456789012345678901234567890123456789abcdef@%5B::1%5D").unwrap();
+        assert_eq!(address.to_string(), "022345678901234567890123456789012345678901234567890123456789abcdef@[::1]:9735");
+    }
+
+    #[test]
+    fn hostname_is_not_scheme() {
+        let address = P2PAddress::parse_uri("lightning:022345678901234567890123456789012345678901234567890123456789abcdef@example.com:1234").unwrap();
+        assert_eq!(address.host.to_string(), "example.com");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn to_uri_roundtrip() {
+        let address = ADDRESS.parse::<P2PAddress>().unwrap();
+        let uri = address.to_uri();
+        assert_eq!(uri, "lightning:022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1:1234");
+        assert_eq!(P2PAddress::parse_uri(&uri).unwrap().to_string(), ADDRESS);
+    }
+
+    fn check_error(input: &str, expected: &str) {
+        let error = P2PAddress::parse_uri(input).unwrap_err();
+        #[cfg(feature = "std")]
+        let error = std::error::Error::source(&error).unwrap();
+        assert!(error.to_string().contains(expected), "unexpected error: {}", error);
+    }
+
+    #[test]
+    fn errors() {
+        check_error("bitcoin:022345678901234567890123456789012345678901234567890123456789abcdef@127.0.0.1", "unsupported scheme");
+        check_error("lightning:022345678901234567890123456789012345678901234567890123456789abcdef%4", "invalid percent encoding at position 76");
+        check_error("lightning:022345678901234567890123456789012345678901234567890123456789abcdef%zz127.0.0.1", "invalid percent encoding at position 76");
+        check_error("lightning:022345678901234567890123456789012345678901234567890123456789abcdef@%ff", "not valid UTF-8");
+        check_error("lightning:lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypq", "invalid address");
+        check_error(&alloc::format!("lightning:{}", "a".repeat(400)), "longer than 328 bytes");
+    }
+}
@@ -0,0 +1,723 @@
+//! Payment hash, preimage and secret
+//!
+//! This module provides the [`PaymentHash`], [`PaymentPreimage`] and [`PaymentSecret`] types and
+//! the related error types.
+//!
+//! All three are 32-byte values which are easy to mix up with each other and with other hashes.
+//! The newtypes prevent this and make the relationship between hash and preimage explicit.
+//!
+//! ## Example
+//!
+//! ```
+//! use ln_types::payment::{PaymentHash, PaymentPreimage};
+//!
+//! let preimage = "0000000000000000000000000000000000000000000000000000000000000000".parse::<PaymentPreimage>().unwrap();
+//! let hash = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925".parse::<PaymentHash>().unwrap();
+//! assert_eq!(preimage.payment_hash(), hash);
+//! assert!(hash.verify(&preimage));
+//! // LND REST API returns base64
+//! assert_eq!(PaymentHash::parse_base64("Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU=").unwrap(), hash);
+//! ```
+
+use core::convert::{TryFrom, TryInto};
+use core::str::FromStr;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Generates the conversions, formatting and integrations common to all types in this module.
+///
+/// `Debug` and `slog::Value` are intentionally not generated because preimage needs to hide them.
+macro_rules! impl_payment_type {
+    ($type:ident, $what:literal) => {
+        impl $type {
+            /// Puts the byte representation into `Vec<u8>`.
+            ///
+            /// This is meant for convenience around APIs that require `Vec<u8>`. Since it
+            /// allocates it's best to avoid it if possible.
+            #[cfg(feature = "alloc")]
+            pub fn to_vec(self) -> Vec<u8> {
+                self.0.to_vec()
+            }
+
+            /// Convenience conversion to byte array.
+            ///
+            /// This can be used instead of `From` to avoid inference issues.
+            pub fn to_array(self) -> [u8; 32] {
+                self.0
+            }
+
+            /// Parses base64 encoding as used by LND REST API.
+            ///
+            /// Both standard and URL-safe alphabets are accepted, the padding is optional.
+            pub fn parse_base64(s: &str) -> Result<Self, ParseError> {
+                decode_base64(s).map($type).map_err(|reason| ParseError {
+                    #[cfg(feature = "alloc")]
+                    input: s.into(),
+                    what: $what,
+                    reason,
+                })
+            }
+
+            /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+            #[cfg(feature = "alloc")]
+            #[inline]
+            fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+                decode_hex(s.as_ref()).map($type).map_err(|error| ParseError {
+                    input: s.into(),
+                    what: $what,
+                    reason: error,
+                })
+            }
+
+            #[cfg(not(feature = "alloc"))]
+            #[inline]
+            fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+                decode_hex(s.as_ref()).map($type).map_err(|error| ParseError {
+                    what: $what,
+                    reason: error,
+                })
+            }
+        }
+
+        /// Shows the value as hex
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::LowerHex::fmt(self, f)
+            }
+        }
+
+        /// Same as Display
+        impl fmt::LowerHex for $type {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_hex(&self.0, f, false)
+            }
+        }
+
+        /// As `Display` but with upper-case letters
+        impl fmt::UpperHex for $type {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_hex(&self.0, f, true)
+            }
+        }
+
+        /// Expects hex representation
+        impl FromStr for $type {
+            type Err = ParseError;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::internal_parse(s)
+            }
+        }
+
+        /// Expects hex representation
+        impl<'a> TryFrom<&'a str> for $type {
+            type Error = ParseError;
+
+            #[inline]
+            fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+                Self::internal_parse(s)
+            }
+        }
+
+        /// Expects hex representation
+        #[cfg(feature = "alloc")]
+        impl TryFrom<String> for $type {
+            type Error = ParseError;
+
+            #[inline]
+            fn try_from(s: String) -> Result<Self, Self::Error> {
+                Self::internal_parse(s)
+            }
+        }
+
+        /// Expects hex representation
+        #[cfg(feature = "alloc")]
+        impl TryFrom<Box<str>> for $type {
+            type Error = ParseError;
+
+            #[inline]
+            fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+                Self::internal_parse(s)
+            }
+        }
+
+        impl<'a> TryFrom<&'a [u8]> for $type {
+            type Error = DecodeError;
+
+            #[inline]
+            fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+                slice.try_into()
+                    .map($type)
+                    .map_err(|_| DecodeError { len: slice.len() })
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl TryFrom<Vec<u8>> for $type {
+            type Error = DecodeError;
+
+            #[inline]
+            fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+                (*vec).try_into()
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl TryFrom<Box<[u8]>> for $type {
+            type Error = DecodeError;
+
+            #[inline]
+            fn try_from(slice: Box<[u8]>) -> Result<Self, Self::Error> {
+                (*slice).try_into()
+            }
+        }
+
+        impl From<[u8; 32]> for $type {
+            fn from(value: [u8; 32]) -> Self {
+                $type(value)
+            }
+        }
+
+        impl From<$type> for [u8; 32] {
+            fn from(value: $type) -> Self {
+                value.0
+            }
+        }
+
+        impl AsRef<[u8; 32]> for $type {
+            fn as_ref(&self) -> &[u8; 32] {
+                &self.0
+            }
+        }
+
+        impl AsRef<[u8]> for $type {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl core::borrow::Borrow<[u8; 32]> for $type {
+            fn borrow(&self) -> &[u8; 32] {
+                &self.0
+            }
+        }
+
+        impl core::borrow::Borrow<[u8]> for $type {
+            fn borrow(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        #[cfg(feature = "parse_arg")]
+        impl parse_arg::ParseArgFromStr for $type {
+            fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+                writer.write_str(concat!("a hex-encoded ", $what, " (64 hex digits/32 bytes)"))
+            }
+        }
+
+        /// Serialized as hex to human-readable formats and as bytes to non-human-readable.
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(self)
+                } else {
+                    serializer.serialize_bytes(&self.0)
+                }
+            }
+        }
+
+        /// Deserialized as hex from human-readable formats and as bytes from non-human-readable.
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+                let visitor = serde_impl::Visitor { what: $what };
+                let bytes = if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(visitor)?
+                } else {
+                    deserializer.deserialize_bytes(visitor)?
+                };
+                Ok($type(bytes))
+            }
+        }
+
+        /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+        ///
+        /// Stored as bytes if `BYTEA` is used, as hex string otherwise.
+        #[cfg(feature = "postgres-types")]
+        impl postgres_types::ToSql for $type {
+            fn to_sql(&self, ty: &postgres_types::Type, out: &mut bytes::BytesMut) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Send + Sync + 'static>> {
+                postgres_impl::to_sql(&self.0, ty, out)
+            }
+
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                postgres_impl::accepts(ty)
+            }
+
+            postgres_types::to_sql_checked!();
+        }
+
+        /// Supports `BYTEA`, `TEXT`, and `VARCHAR`.
+        ///
+        /// Decoded as bytes if `BYTEA` is used, as hex string otherwise.
+        #[cfg(feature = "postgres-types")]
+        impl<'a> postgres_types::FromSql<'a> for $type {
+            fn from_sql(ty: &postgres_types::Type, raw: &'a [u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
+                use postgres_types::Type;
+
+                match *ty {
+                    Type::BYTEA => <&[u8]>::from_sql(ty, raw)?.try_into().map_err(|error: DecodeError| Box::new(error) as _),
+                    _ => <&str>::from_sql(ty, raw)?.parse().map_err(|error: ParseError| Box::new(error) as _),
+                }
+            }
+
+            fn accepts(ty: &postgres_types::Type) -> bool {
+                postgres_impl::accepts(ty)
+            }
+        }
+    }
+}
+
+/// Hash of [`PaymentPreimage`] identifying a payment.
+///
+/// The payment hash is public - it's contained in invoices and HTLCs.
+///
+/// ## Example
+///
+/// ```
+/// let hash = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925".parse::<ln_types::PaymentHash>().unwrap();
+/// assert_eq!(format!("{:X}", hash), "66687AADF862BD776C8FC18B8E9F8E20089714856EE233B3902A591D0D5F2925");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PaymentHash([u8; 32]);
+
+impl PaymentHash {
+    /// Checks whether the preimage hashes to this payment hash.
+    ///
+    /// The comparison is performed in constant time so that this can be used to verify preimages
+    /// supplied by untrusted parties.
+    pub fn verify(&self, preimage: &PaymentPreimage) -> bool {
+        let hash = preimage.payment_hash();
+        let difference = self.0.iter().zip(&hash.0).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        // Prevents the compiler from turning the loop above into a short-circuiting comparison.
+        // SAFETY: the reference is valid and aligned since it was created from a local variable.
+        unsafe { core::ptr::read_volatile(&difference) == 0 }
+    }
+}
+
+impl_payment_type!(PaymentHash, "payment hash");
+
+/// Same as Display
+impl fmt::Debug for PaymentHash {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Secret revealed by the payee to prove that the payment was received.
+///
+/// The preimage must be kept secret until the payment is settled. To avoid accidentally leaking
+/// it into logs the `Debug` implementation (and `slog::Value` if enabled) only shows the
+/// corresponding [`PaymentHash`]. `Display` and hex formatting traits still show the preimage
+/// since they are used to explicitly encode it.
+///
+/// ## Example
+///
+/// ```
+/// let preimage = "0000000000000000000000000000000000000000000000000000000000000000".parse::<ln_types::PaymentPreimage>().unwrap();
+/// assert_eq!(format!("{:?}", preimage), "PaymentPreimage { payment_hash: 66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925, .. }");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PaymentPreimage([u8; 32]);
+
+impl PaymentPreimage {
+    /// Computes the payment hash (SHA256 of the preimage).
+    pub fn payment_hash(&self) -> PaymentHash {
+        let mut hasher = crate::hashes::Sha256::new();
+        hasher.update(&self.0);
+        PaymentHash(hasher.finalize())
+    }
+}
+
+impl_payment_type!(PaymentPreimage, "payment preimage");
+
+/// Shows the payment hash instead of the preimage to avoid leaking it.
+impl fmt::Debug for PaymentPreimage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PaymentPreimage")
+            .field("payment_hash", &self.payment_hash())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Secret included in the invoice which prevents intermediate nodes from probing the recipient.
+///
+/// ## Example
+///
+/// ```
+/// let secret = "1111111111111111111111111111111111111111111111111111111111111111".parse::<ln_types::PaymentSecret>().unwrap();
+/// assert_eq!(secret.to_array(), [0x11; 32]);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PaymentSecret([u8; 32]);
+
+impl_payment_type!(PaymentSecret, "payment secret");
+
+/// Same as Display
+impl fmt::Debug for PaymentSecret {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Formats the bytes as hex respecting width and fill.
+fn fmt_hex(bytes: &[u8; 32], f: &mut fmt::Formatter, upper: bool) -> fmt::Result {
+    #[cfg(feature = "hex-conservative")]
+    {
+        if upper {
+            hex_conservative::fmt_hex_exact!(f, 32, bytes, hex_conservative::Case::Upper)
+        } else {
+            hex_conservative::fmt_hex_exact!(f, 32, bytes, hex_conservative::Case::Lower)
+        }
+    }
+
+    #[cfg(not(feature = "hex-conservative"))]
+    {
+        use fmt::Write;
+
+        if let Some(width) = f.width() {
+            for _ in 0..width.saturating_sub(64) {
+                f.write_char(f.fill())?;
+            }
+        }
+        for byte in bytes {
+            if upper {
+                write!(f, "{:02X}", byte)?;
+            } else {
+                write!(f, "{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decode_hex(s: &str) -> Result<[u8; 32], ParseErrorInner> {
+    fn decode_digit(digit: u8, pos: usize, s: &str) -> Result<u8, ParseErrorInner> {
+        match digit {
+            b'0'..=b'9' => Ok(digit - b'0'),
+            b'a'..=b'f' => Ok(digit - b'a' + 10),
+            b'A'..=b'F' => Ok(digit - b'A' + 10),
+            _ => Err(ParseErrorInner::Char { pos, c: s.chars().nth(pos).unwrap(), }),
+        }
+    }
+
+    let mut result = [0; 32];
+
+    if s.len() != 64 {
+        return Err(ParseErrorInner::Length)
+    }
+
+    for ((i, pair), dst) in s.as_bytes().chunks_exact(2).enumerate().zip(&mut result) {
+        *dst = decode_digit(pair[0], i * 2, s)? * 16 + decode_digit(pair[1], i * 2 + 1, s)?;
+    }
+
+    Ok(result)
+}
+
+fn decode_base64(s: &str) -> Result<[u8; 32], ParseErrorInner> {
+    // 32 bytes are encoded as 43 digits (258 bits) followed by a single padding character
+    let digits = s.strip_suffix('=').unwrap_or(s);
+    if digits.len() != 43 {
+        return Err(ParseErrorInner::Base64Length);
+    }
+
+    let mut result = [0; 32];
+    let mut out = result.iter_mut();
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for (pos, digit) in digits.bytes().enumerate() {
+        let value = match digit {
+            b'A'..=b'Z' => digit - b'A',
+            b'a'..=b'z' => digit - b'a' + 26,
+            b'0'..=b'9' => digit - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            // all previous bytes are ASCII so `pos` is at char boundary
+            _ => return Err(ParseErrorInner::Base64Char { pos, c: s[pos..].chars().next().unwrap(), }),
+        };
+        acc = acc << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            *out.next().expect("43 digits decode to exactly 32 bytes") = (acc >> bits) as u8;
+            acc &= (1 << bits) - 1;
+        }
+    }
+
+    if acc != 0 {
+        return Err(ParseErrorInner::Base64TrailingBits);
+    }
+
+    Ok(result)
+}
+
+/// Error returned when decoding raw bytes fails
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    len: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid length {} bytes, the length must be 32 bytes", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The string that was attempted to be parsed
+    #[cfg(feature = "alloc")]
+    input: String,
+    /// Name of the type being parsed
+    what: &'static str,
+    /// Information about what exactly went wrong
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to parse{} {}", opt_fmt!("alloc", format_args!(" '{}' as", &self.input)), self.what; &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// Details about the error.
+///
+/// This is private to avoid committing to a representation.
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    /// Length != 64 chars
+    Length,
+    Char { pos: usize, c: char, },
+    Base64Length,
+    Base64Char { pos: usize, c: char, },
+    Base64TrailingBits,
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::Length => f.write_str("invalid length (must be 64 chars)"),
+            ParseErrorInner::Char { c, pos, } => write!(f, "invalid character '{}' at position {} (must be hex digit)", c, pos),
+            ParseErrorInner::Base64Length => f.write_str("invalid length (must be 43 base64 digits optionally followed by '=')"),
+            ParseErrorInner::Base64Char { c, pos, } => write!(f, "invalid character '{}' at position {} (must be base64 digit)", c, pos),
+            ParseErrorInner::Base64TrailingBits => f.write_str("the unused trailing bits of base64 are not zero"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {}
+
+/// Helpers for implementations of `serde` traits
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use core::convert::TryInto;
+    use serde::de::Error;
+
+    /// Visitor decoding hex from human-readable formats and bytes from non-human-readable.
+    pub(super) struct Visitor {
+        pub(super) what: &'static str,
+    }
+
+    impl<'de> serde::de::Visitor<'de> for Visitor {
+        type Value = [u8; 32];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(formatter, "a {} as 64 digits long hex string or 32 bytes", self.what)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            use super::ParseErrorInner;
+
+            super::decode_hex(v).map_err(|error| {
+                match error {
+                    ParseErrorInner::Char { c, pos: _, } => E::invalid_value(serde::de::Unexpected::Char(c), &"a hex digit"),
+                    _ => E::invalid_length(v.len(), &"64 hex digits"),
+                }
+            })
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> where E: Error {
+            v.try_into().map_err(|_| E::invalid_length(v.len(), &"32 bytes"))
+        }
+    }
+}
+
+/// Helpers for implementations of `postgres-types` traits
+#[cfg(feature = "postgres-types")]
+mod postgres_impl {
+    use alloc::boxed::Box;
+    use postgres_types::{ToSql, IsNull, Type};
+    use bytes::BytesMut;
+    use std::error::Error;
+
+    pub(super) fn to_sql(bytes: &[u8; 32], ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+        use core::fmt::Write;
+
+        match *ty {
+            Type::BYTEA => (bytes as &[_]).to_sql(ty, out),
+            _ => {
+                for byte in bytes {
+                    write!(out, "{:02x}", byte).map_err(|error| Box::new(error) as Box<dyn Error + Send + Sync>)?;
+                }
+                Ok(IsNull::No)
+            },
+        }
+    }
+
+    pub(super) fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::BYTEA | Type::TEXT | Type::VARCHAR)
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::{PaymentHash, PaymentPreimage, PaymentSecret};
+    use slog::{Key, Value, Record, Serializer};
+
+    /// Currently uses `Display` but may use `emit_bytes` if/when it's implemented.
+    impl Value for PaymentHash {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    /// Logs the payment hash instead of the preimage to avoid leaking it.
+    impl Value for PaymentPreimage {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{:?}", self))
+        }
+    }
+
+    /// Currently uses `Display` but may use `emit_bytes` if/when it's implemented.
+    impl Value for PaymentSecret {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    impl_error_value!(super::ParseError, super::DecodeError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PaymentHash, PaymentPreimage, PaymentSecret};
+    use alloc::format;
+
+    const PREIMAGE: &str = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+    const HASH: &str = "ae216c2ef5247a3782c135efa279a3e4cdc61094270f5d2be58c6204b7a612c9";
+
+    #[test]
+    fn payment_hash() {
+        let preimage = PREIMAGE.parse::<PaymentPreimage>().unwrap();
+        let hash = HASH.parse::<PaymentHash>().unwrap();
+        assert_eq!(preimage.payment_hash(), hash);
+        assert!(hash.verify(&preimage));
+        assert!(!PaymentHash::from([0; 32]).verify(&preimage));
+    }
+
+    #[test]
+    fn preimage_debug_is_redacted() {
+        let preimage = PREIMAGE.parse::<PaymentPreimage>().unwrap();
+        let debug = format!("{:?}", preimage);
+        assert!(!debug.contains(PREIMAGE));
+        assert!(debug.contains(HASH));
+        assert_eq!(format!("{}", preimage), PREIMAGE);
+    }
+
+    #[test]
+    fn hex_formatting() {
+        let secret = PaymentSecret::from([0xab; 32]);
+        assert_eq!(format!("{:X}", secret), "AB".repeat(32));
+        assert_eq!(format!("{:>66}", secret), format!("  {}", "ab".repeat(32)));
+        assert_eq!(format!("{:*>66x}", secret), format!("**{}", "ab".repeat(32)));
+    }
+
+    #[test]
+    fn base64() {
+        let hash = HASH.parse::<PaymentHash>().unwrap();
+        assert_eq!(PaymentHash::parse_base64("riFsLvUkejeCwTXvonmj5M3GEJQnD10r5YxiBLemEsk=").unwrap(), hash);
+        assert_eq!(PaymentHash::parse_base64("riFsLvUkejeCwTXvonmj5M3GEJQnD10r5YxiBLemEsk").unwrap(), hash);
+        let zero_hash = PaymentPreimage::from([0; 32]).payment_hash();
+        assert_eq!(PaymentHash::parse_base64("Zmh6rfhivXdsj8GLjp+OIAiXFIVu4jOzkCpZHQ1fKSU=").unwrap(), zero_hash);
+        assert_eq!(PaymentHash::parse_base64("Zmh6rfhivXdsj8GLjp-OIAiXFIVu4jOzkCpZHQ1fKSU").unwrap(), zero_hash);
+    }
+
+    fn base64_error(input: &str) -> alloc::string::String {
+        let error = PaymentHash::parse_base64(input).unwrap_err();
+        #[cfg(feature = "std")]
+        let error = std::error::Error::source(&error).unwrap();
+        alloc::string::ToString::to_string(&error)
+    }
+
+    #[test]
+    fn base64_errors() {
+        assert!(base64_error("riFsLvUkejeCwTXvonmj5M3GEJQnD10r5YxiBLemEsk==").contains("must be 43 base64 digits"));
+        assert!(base64_error("riFsLvUkejeCwTXvonmj5M3GEJQnD10r5YxiBLemEs!=").contains("invalid character '!' at position 42"));
+        assert!(base64_error("riFsLvUkejeCwTXvonmj5M3GEJQnD10r5YxiBLemEsl=").contains("trailing bits"));
+    }
+
+    chk_err_impl! {
+        parse_payment_hash_error_empty, "", PaymentHash, [
+            "failed to parse '' as payment hash",
+            "invalid length (must be 64 chars)",
+        ], [
+            "failed to parse payment hash",
+            "invalid length (must be 64 chars)",
+        ];
+        parse_payment_preimage_error_char, "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fzz", PaymentPreimage, [
+            "failed to parse '0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fzz' as payment preimage",
+            "invalid character 'z' at position 62 (must be hex digit)",
+        ], [
+            "failed to parse payment preimage",
+            "invalid character 'z' at position 62 (must be hex digit)",
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let hash = HASH.parse::<PaymentHash>().unwrap();
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", HASH));
+        assert_eq!(serde_json::from_str::<PaymentHash>(&json).unwrap(), hash);
+        let bytes = bincode::serialize(&hash).unwrap();
+        assert_eq!(bincode::deserialize::<PaymentHash>(&bytes).unwrap(), hash);
+        assert!(serde_json::from_str::<PaymentPreimage>("\"00\"").is_err());
+    }
+}
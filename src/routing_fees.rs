@@ -0,0 +1,495 @@
+//! Routing fee policy of a channel.
+//!
+//! This module provides the [`RoutingFees`] type and the related error types.
+
+use core::fmt;
+use core::str::FromStr;
+use core::convert::TryFrom;
+use crate::amount::Amount;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String};
+
+const MILLION: u128 = 1_000_000;
+
+/// Fees charged by a node for forwarding a payment through a channel.
+///
+/// As defined in BOLT 7 the fee for forwarding `amount_to_forward` is
+/// `base + amount_to_forward * proportional_millionths / 1 000 000` where the division rounds
+/// down.
+/// This type implements the computation correctly so that applications don't have to.
+///
+/// ## `Display` implementation
+///
+/// The fees are displayed as `<base> + <proportional_millionths> ppm` - e.g. `1000 msat + 100 ppm`.
+/// The same format is accepted by the parser except the base may use any denomination supported
+/// by [`Amount`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct RoutingFees {
+    /// Fixed fee charged for each forwarded payment (`fee_base_msat`).
+    pub base: Amount,
+    /// Fee charged per million of forwarded millisatoshis (`fee_proportional_millionths`).
+    pub proportional_millionths: u32,
+}
+
+impl RoutingFees {
+    /// No fees at all.
+    pub const ZERO: RoutingFees = RoutingFees { base: Amount::ZERO, proportional_millionths: 0, };
+
+    /// Computes the fee charged for forwarding `amount_to_forward`.
+    ///
+    /// Returns `None` if the fee would exceed the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::{Amount, RoutingFees};
+    ///
+    /// let fees = RoutingFees { base: Amount::ONE_SAT, proportional_millionths: 100 };
+    /// let amount = Amount::from_msat(1_234_567).unwrap();
+    /// // 1000 + 1234567 * 100 / 1000000 = 1000 + 123 (rounded down)
+    /// assert_eq!(fees.fee(amount).unwrap().to_msat(), 1_123);
+    /// ```
+    pub fn fee(&self, amount_to_forward: Amount) -> Option<Amount> {
+        let proportional = u128::from(amount_to_forward.to_msat()) * u128::from(self.proportional_millionths) / MILLION;
+        // If this overflowed u64 it's way above supply cap anyway.
+        let proportional = u64::try_from(proportional).ok()?;
+        self.base.checked_add(Amount::from_msat(proportional).ok()?)
+    }
+
+    /// Computes the amount that has to be sent to this node so that it forwards `amount_to_forward`.
+    ///
+    /// This is `amount_to_forward` plus fee.
+    /// Returns `None` if the result would exceed the supply cap.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::{Amount, RoutingFees};
+    ///
+    /// let fees = RoutingFees { base: Amount::ONE_SAT, proportional_millionths: 100 };
+    /// let amount = Amount::from_msat(1_234_567).unwrap();
+    /// assert_eq!(fees.incoming_amount(amount).unwrap().to_msat(), 1_235_690);
+    /// ```
+    pub fn incoming_amount(&self, amount_to_forward: Amount) -> Option<Amount> {
+        amount_to_forward.checked_add(self.fee(amount_to_forward)?)
+    }
+
+    /// Computes the largest amount this node will forward if it receives `incoming_amount`.
+    ///
+    /// This is the inverse of [`incoming_amount`](Self::incoming_amount) with rounding matching
+    /// BOLT 7: the returned amount is the largest one for which the fee still fits in
+    /// `incoming_amount`.
+    /// Returns `None` if `incoming_amount` doesn't cover even the base fee.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ln_types::{Amount, RoutingFees};
+    ///
+    /// let fees = RoutingFees { base: Amount::ONE_SAT, proportional_millionths: 100 };
+    /// let incoming = Amount::from_msat(1_235_690).unwrap();
+    /// assert_eq!(fees.max_amount_to_forward(incoming).unwrap().to_msat(), 1_234_567);
+    /// assert!(fees.max_amount_to_forward(Amount::from_msat(999).unwrap()).is_none());
+    /// ```
+    pub fn max_amount_to_forward(&self, incoming_amount: Amount) -> Option<Amount> {
+        let available = u128::from(incoming_amount.checked_sub(self.base)?.to_msat());
+        let proportional = u128::from(self.proportional_millionths);
+        // We're looking for the largest `x` such that `x + floor(x * p / M) <= available`.
+        // `floor(available * M / (M + p))` satisfies it and the solution is at most one larger
+        // because the floored fee undershoots the exact one by less than one millisatoshi.
+        let estimate = available * MILLION / (MILLION + proportional);
+        let next = estimate + 1;
+        let amount = if next + next * proportional / MILLION <= available {
+            next
+        } else {
+            estimate
+        };
+        // The amount is at most `available` which is within bounds.
+        Some(Amount::from_msat(amount as u64).expect("amount can't be larger than incoming amount"))
+    }
+
+    /// Parses the fees without wrapping the error.
+    fn parse_raw(s: &str) -> Result<Self, ParseErrorInner> {
+        let plus_pos = s.find('+').ok_or(ParseErrorInner::MissingPlus)?;
+        let base = s[..plus_pos].trim();
+        let proportional = s[(plus_pos + 1)..].trim();
+
+        let base = base.parse().map_err(ParseErrorInner::InvalidBase)?;
+        let proportional_len = proportional.len().checked_sub(3).ok_or(ParseErrorInner::MissingPpm)?;
+        match proportional.get(proportional_len..) {
+            Some(suffix) if suffix.eq_ignore_ascii_case("ppm") => (),
+            _ => return Err(ParseErrorInner::MissingPpm),
+        }
+        let proportional_millionths = proportional[..proportional_len]
+            .trim_end()
+            .parse()
+            .map_err(ParseErrorInner::InvalidProportional)?;
+
+        Ok(RoutingFees {
+            base,
+            proportional_millionths,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
+}
+
+/// Displays the fees as `<base> + <proportional_millionths> ppm`.
+impl fmt::Display for RoutingFees {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} + {} ppm", self.base, self.proportional_millionths)
+    }
+}
+
+/// Accepts `<base> + <proportional_millionths> ppm`, e.g. `1 sat + 100 ppm`.
+///
+/// The base accepts the same format as [`Amount`], whitespace around `+` is optional.
+impl FromStr for RoutingFees {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts `<base> + <proportional_millionths> ppm`, e.g. `1 sat + 100 ppm`.
+///
+/// The base accepts the same format as [`Amount`], whitespace around `+` is optional.
+impl<'a> TryFrom<&'a str> for RoutingFees {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts `<base> + <proportional_millionths> ppm`, e.g. `1 sat + 100 ppm`.
+///
+/// The base accepts the same format as [`Amount`], whitespace around `+` is optional.
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for RoutingFees {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Accepts `<base> + <proportional_millionths> ppm`, e.g. `1 sat + 100 ppm`.
+///
+/// The base accepts the same format as [`Amount`], whitespace around `+` is optional.
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for RoutingFees {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    #[cfg(feature = "alloc")]
+    input: String,
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        {
+            write_err!(f, "failed to parse '{}' as routing fees", self.input; &self.reason)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            write_err!(f, "failed to parse routing fees"; &self.reason)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    MissingPlus,
+    InvalidBase(crate::amount::ParseError),
+    MissingPpm,
+    InvalidProportional(core::num::ParseIntError),
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::MissingPlus => f.write_str("missing '+' separating base fee from proportional fee"),
+            ParseErrorInner::InvalidBase(error) => write_err!(f, "invalid base fee"; error),
+            ParseErrorInner::MissingPpm => f.write_str("missing 'ppm' suffix after proportional fee"),
+            ParseErrorInner::InvalidProportional(error) => write_err!(f, "invalid proportional fee"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseErrorInner::MissingPlus | ParseErrorInner::MissingPpm => None,
+            ParseErrorInner::InvalidBase(error) => Some(error),
+            ParseErrorInner::InvalidProportional(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(feature = "parse_arg")]
+mod parse_arg_impl {
+    use core::fmt;
+    use super::RoutingFees;
+
+    impl parse_arg::ParseArgFromStr for RoutingFees {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("routing fees in the form `<base amount> + <proportional fee> ppm`, e.g. `1 sat + 100 ppm`")
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use super::RoutingFees;
+    use crate::amount::Amount;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
+    use core::convert::TryInto;
+
+    #[cfg(feature = "serde_alloc")]
+    use alloc::string::String;
+
+    struct HRVisitor;
+
+    impl<'de> Visitor<'de> for HRVisitor {
+        type Value = RoutingFees;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("routing fees in the form `<base amount> + <proportional fee> ppm`")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            v.try_into().map_err(|error| {
+                E::custom(error)
+            })
+        }
+
+        #[cfg(feature = "serde_alloc")]
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E> where E: Error {
+            v.try_into().map_err(|error| {
+                E::custom(error)
+            })
+        }
+    }
+
+    /// Serialized as string to human-readable formats and as a tuple of base msats and
+    /// proportional millionths otherwise.
+    impl Serialize for RoutingFees {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                (self.base, self.proportional_millionths).serialize(serializer)
+            }
+        }
+    }
+
+    /// Deserialized as string from human-readable formats and as a tuple of base msats and
+    /// proportional millionths otherwise.
+    impl<'de> Deserialize<'de> for RoutingFees {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_str(HRVisitor)
+            } else {
+                let (base, proportional_millionths) = <(Amount, u32)>::deserialize(deserializer)?;
+                Ok(RoutingFees { base, proportional_millionths, })
+            }
+        }
+    }
+}
+
+#[cfg(feature = "postgres-types")]
+mod postgres_impl {
+    use alloc::boxed::Box;
+    use super::RoutingFees;
+    use postgres_types::{ToSql, FromSql, IsNull, Type};
+    use bytes::BytesMut;
+    use std::error::Error;
+
+    /// Stores the value as text (same types as `&str`)
+    impl ToSql for RoutingFees {
+        fn to_sql(&self, _ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+            use core::fmt::Write;
+
+            write!(out, "{}", self).map(|_| IsNull::No).map_err(|error| Box::new(error) as _)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <&str as ToSql>::accepts(ty)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    /// Retrieves the value as text (same types as `&str`)
+    impl<'a> FromSql<'a> for RoutingFees {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+            <&str>::from_sql(ty, raw)?.parse().map_err(|error| Box::new(error) as _)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <&str as FromSql>::accepts(ty)
+        }
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::RoutingFees;
+    use slog::{Key, Value, KV, Record, Serializer};
+
+    /// Uses `Display`
+    impl Value for RoutingFees {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    /// Serializes each field separately.
+    ///
+    /// The fields are:
+    ///
+    /// * `base` - delegates to `Amount`
+    /// * `proportional_millionths` - `emit_u32`
+    impl KV for RoutingFees {
+        fn serialize(&self, rec: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+            // `Key` is a type alias but if `slog/dynamic_keys` feature is enabled it's not
+            #![allow(clippy::useless_conversion)]
+            self.base.serialize(rec, Key::from("base"), serializer)?;
+            serializer.emit_u32(Key::from("proportional_millionths"), self.proportional_millionths)?;
+            Ok(())
+        }
+    }
+
+    impl_error_value!(super::ParseError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoutingFees;
+    use crate::amount::Amount;
+    use alloc::string::ToString;
+
+    fn fees(base: u64, proportional_millionths: u32) -> RoutingFees {
+        RoutingFees {
+            base: Amount::from_msat(base).unwrap(),
+            proportional_millionths,
+        }
+    }
+
+    #[test]
+    fn fee_rounds_down() {
+        let fees = fees(1_000, 1);
+        assert_eq!(fees.fee(Amount::from_msat(999_999).unwrap()).unwrap().to_msat(), 1_000);
+        assert_eq!(fees.fee(Amount::from_msat(1_000_000).unwrap()).unwrap().to_msat(), 1_001);
+        assert_eq!(fees.fee(Amount::ZERO).unwrap().to_msat(), 1_000);
+        assert_eq!(RoutingFees::ZERO.fee(Amount::MAX).unwrap(), Amount::ZERO);
+    }
+
+    #[test]
+    fn fee_overflow() {
+        assert!(fees(0, u32::MAX).fee(Amount::MAX).is_none());
+        assert!(fees(1, 1_000_000).incoming_amount(Amount::MAX).is_none());
+        assert_eq!(fees(0, 1_000_000).fee(Amount::MAX).unwrap(), Amount::MAX);
+    }
+
+    #[test]
+    fn inverse() {
+        let policies = [fees(0, 0), fees(1_000, 1), fees(1_000, 100), fees(0, 999_999), fees(5, 1_000_000), fees(1, u32::MAX)];
+        for policy in &policies {
+            for &msat in &[0, 1, 999, 1_000, 1_001, 12_345, 999_999, 1_000_000, 1_000_001, 123_456_789] {
+                let amount = Amount::from_msat(msat).unwrap();
+                let incoming = match policy.incoming_amount(amount) {
+                    Some(incoming) => incoming,
+                    None => continue,
+                };
+                let forwarded = policy.max_amount_to_forward(incoming).unwrap();
+                assert!(forwarded >= amount, "policy: {}, amount: {}", policy, amount);
+                assert!(policy.incoming_amount(forwarded).unwrap() <= incoming, "policy: {}, amount: {}", policy, amount);
+                let too_much = forwarded + Amount::ONE_MSAT;
+                assert!(policy.incoming_amount(too_much).map_or(true, |required| required > incoming), "policy: {}, amount: {}", policy, amount);
+            }
+        }
+        assert!(fees(1_000, 0).max_amount_to_forward(Amount::from_msat(999).unwrap()).is_none());
+        assert_eq!(fees(1_000, 0).max_amount_to_forward(Amount::ONE_SAT).unwrap(), Amount::ZERO);
+    }
+
+    #[test]
+    fn display_parse_roundtrip() {
+        let fees = fees(1_000, 100);
+        assert_eq!(fees.to_string(), "1000 msat + 100 ppm");
+        assert_eq!(fees.to_string().parse::<RoutingFees>().unwrap(), fees);
+        assert_eq!("1 sat+100ppm".parse::<RoutingFees>().unwrap(), fees);
+        assert_eq!("  0.00000001 BTC  +  100 PPM ".parse::<RoutingFees>().unwrap(), fees);
+    }
+
+    chk_err_impl! {
+        parse_routing_fees_error_empty, "", RoutingFees, ["failed to parse '' as routing fees", "missing '+' separating base fee from proportional fee"], ["failed to parse routing fees", "missing '+' separating base fee from proportional fee"];
+        parse_routing_fees_error_missing_ppm, "1000 + 100", RoutingFees, ["failed to parse '1000 + 100' as routing fees", "missing 'ppm' suffix after proportional fee"], ["failed to parse routing fees", "missing 'ppm' suffix after proportional fee"];
+        parse_routing_fees_error_base, "1 eur + 100 ppm", RoutingFees, [
+            "failed to parse '1 eur + 100 ppm' as routing fees",
+            "invalid base fee",
+            "failed to parse '1 eur' as millisatoshis",
+            "unknown denomination (expected btc, sat or msat)",
+        ], [
+            "failed to parse routing fees",
+            "invalid base fee",
+            "failed to parse millisatoshis",
+            "unknown denomination (expected btc, sat or msat)",
+        ];
+        parse_routing_fees_error_proportional, "1000 msat + -1 ppm", RoutingFees, [
+            "failed to parse '1000 msat + -1 ppm' as routing fees",
+            "invalid proportional fee",
+            "invalid digit found in string",
+        ], [
+            "failed to parse routing fees",
+            "invalid proportional fee",
+            "invalid digit found in string",
+        ];
+    }
+}
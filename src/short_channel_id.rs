@@ -0,0 +1,635 @@
+//! Short channel identifier
+//!
+//! This module provides the [`ShortChannelId`] type and the related error types.
+
+use core::convert::{TryFrom, TryInto};
+use core::str::FromStr;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Maximum value of block height and transaction index (24 bits)
+const MAX_24_BITS: u32 = (1 << 24) - 1;
+
+/// Compact identifier of a channel referring to its funding output.
+///
+/// The identifier consists of the height of the block containing the funding transaction
+/// (24 bits), the index of the transaction within the block (24 bits) and the index of the
+/// output (16 bits). It is packed into `u64` in this order, most significant bits first, which is
+/// also how LND presents it. On the wire it's the big-endian representation of the `u64`.
+///
+/// ## `Display` implementation
+///
+/// The identifier is displayed as `BLOCKxTXxOUTPUT` - e.g. `700000x1234x0` - like in BOLTs and
+/// Core Lightning. The parser additionally accepts `:` as the separator and a decimal `u64`.
+///
+/// ## Example
+///
+/// ```
+/// use ln_types::ShortChannelId;
+///
+/// let scid = "700000x1234x1".parse::<ShortChannelId>().unwrap();
+/// assert_eq!(scid.block_height(), 700000);
+/// assert_eq!(scid.tx_index(), 1234);
+/// assert_eq!(scid.output_index(), 1);
+/// assert_eq!(scid.to_u64(), 769658139524071425);
+/// assert_eq!("769658139524071425".parse::<ShortChannelId>().unwrap(), scid);
+/// assert_eq!("700000:1234:1".parse::<ShortChannelId>().unwrap(), scid);
+/// assert_eq!(scid.to_string(), "700000x1234x1");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ShortChannelId(u64);
+
+impl ShortChannelId {
+    /// Constructs the identifier from its components.
+    ///
+    /// Returns an error if block height or transaction index doesn't fit into 24 bits.
+    pub fn from_parts(block_height: u32, tx_index: u32, output_index: u16) -> Result<Self, OutOfRangeError> {
+        if block_height > MAX_24_BITS {
+            return Err(OutOfRangeError { component: Component::BlockHeight, value: block_height });
+        }
+        if tx_index > MAX_24_BITS {
+            return Err(OutOfRangeError { component: Component::TxIndex, value: tx_index });
+        }
+        Ok(ShortChannelId(u64::from(block_height) << 40 | u64::from(tx_index) << 16 | u64::from(output_index)))
+    }
+
+    /// Creates the identifier from packed `u64` representation.
+    ///
+    /// All `u64` values are valid identifiers.
+    #[inline]
+    pub const fn from_u64(value: u64) -> Self {
+        ShortChannelId(value)
+    }
+
+    /// Returns packed `u64` representation.
+    #[inline]
+    pub const fn to_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Creates the identifier from its wire (big-endian) representation.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; 8]) -> Self {
+        ShortChannelId(u64::from_be_bytes(bytes))
+    }
+
+    /// Returns the wire (big-endian) representation.
+    #[inline]
+    pub fn to_bytes(self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    /// Height of the block containing the funding transaction.
+    #[inline]
+    pub const fn block_height(self) -> u32 {
+        (self.0 >> 40) as u32
+    }
+
+    /// Index of the funding transaction within the block.
+    #[inline]
+    pub const fn tx_index(self) -> u32 {
+        (self.0 >> 16) as u32 & MAX_24_BITS
+    }
+
+    /// Index of the funding output within the transaction.
+    #[inline]
+    pub const fn output_index(self) -> u16 {
+        self.0 as u16
+    }
+
+    /// Internal monomorphic parsing method.
+    ///
+    /// This should improve codegen without requiring allocations.
+    fn parse_raw(s: &str) -> Result<Self, ParseErrorInner> {
+        let separator = match (s.contains('x'), s.contains(':')) {
+            (false, false) => return s.parse().map(ShortChannelId).map_err(ParseErrorInner::InvalidU64),
+            (true, false) => 'x',
+            (false, true) => ':',
+            (true, true) => return Err(ParseErrorInner::MixedSeparators),
+        };
+
+        let mut parts = s.split(separator);
+        let mut parse_next = |component| {
+            parts.next()
+                .ok_or(ParseErrorInner::MissingComponent(component))?
+                .parse::<u32>()
+                .map_err(|error| ParseErrorInner::InvalidNumber(component, error))
+        };
+        let block_height = parse_next(Component::BlockHeight)?;
+        let tx_index = parse_next(Component::TxIndex)?;
+        let output_index = parse_next(Component::OutputIndex)?;
+        if parts.next().is_some() {
+            return Err(ParseErrorInner::TooManyComponents);
+        }
+        let output_index = output_index.try_into()
+            .map_err(|_| OutOfRangeError { component: Component::OutputIndex, value: output_index })?;
+
+        Self::from_parts(block_height, tx_index, output_index).map_err(Into::into)
+    }
+
+    /// Generic wrapper for parsing that is used to implement parsing from multiple types.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn internal_parse<S: AsRef<str> + Into<String>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            input: s.into(),
+            reason: error,
+        })
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[inline]
+    fn internal_parse<S: AsRef<str>>(s: S) -> Result<Self, ParseError> {
+        Self::parse_raw(s.as_ref()).map_err(|error| ParseError {
+            reason: error,
+        })
+    }
+}
+
+/// Displays the identifier as `BLOCKxTXxOUTPUT`
+impl fmt::Display for ShortChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}x{}", self.block_height(), self.tx_index(), self.output_index())
+    }
+}
+
+/// Same as Display
+impl fmt::Debug for ShortChannelId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Expects `BLOCKxTXxOUTPUT`, `BLOCK:TX:OUTPUT` or decimal `u64`
+impl FromStr for ShortChannelId {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `BLOCKxTXxOUTPUT`, `BLOCK:TX:OUTPUT` or decimal `u64`
+impl<'a> TryFrom<&'a str> for ShortChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `BLOCKxTXxOUTPUT`, `BLOCK:TX:OUTPUT` or decimal `u64`
+#[cfg(feature = "alloc")]
+impl TryFrom<String> for ShortChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects `BLOCKxTXxOUTPUT`, `BLOCK:TX:OUTPUT` or decimal `u64`
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<str>> for ShortChannelId {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(s: Box<str>) -> Result<Self, Self::Error> {
+        Self::internal_parse(s)
+    }
+}
+
+/// Expects 8 bytes in big-endian order
+impl<'a> TryFrom<&'a [u8]> for ShortChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: &'a [u8]) -> Result<Self, Self::Error> {
+        let bytes = slice.try_into().map_err(|_| DecodeError { len: slice.len() })?;
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+/// Expects 8 bytes in big-endian order
+#[cfg(feature = "alloc")]
+impl TryFrom<Vec<u8>> for ShortChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+        (*vec).try_into()
+    }
+}
+
+/// Expects 8 bytes in big-endian order
+#[cfg(feature = "alloc")]
+impl TryFrom<Box<[u8]>> for ShortChannelId {
+    type Error = DecodeError;
+
+    #[inline]
+    fn try_from(slice: Box<[u8]>) -> Result<Self, Self::Error> {
+        (*slice).try_into()
+    }
+}
+
+impl From<u64> for ShortChannelId {
+    #[inline]
+    fn from(value: u64) -> Self {
+        ShortChannelId(value)
+    }
+}
+
+impl From<ShortChannelId> for u64 {
+    #[inline]
+    fn from(value: ShortChannelId) -> Self {
+        value.0
+    }
+}
+
+/// Big-endian order
+impl From<[u8; 8]> for ShortChannelId {
+    #[inline]
+    fn from(value: [u8; 8]) -> Self {
+        Self::from_bytes(value)
+    }
+}
+
+/// Big-endian order
+impl From<ShortChannelId> for [u8; 8] {
+    #[inline]
+    fn from(value: ShortChannelId) -> Self {
+        value.to_bytes()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Component {
+    BlockHeight,
+    TxIndex,
+    OutputIndex,
+}
+
+impl Component {
+    fn max(self) -> u32 {
+        match self {
+            Component::BlockHeight | Component::TxIndex => MAX_24_BITS,
+            Component::OutputIndex => u16::MAX.into(),
+        }
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Component::BlockHeight => f.write_str("block height"),
+            Component::TxIndex => f.write_str("transaction index"),
+            Component::OutputIndex => f.write_str("output index"),
+        }
+    }
+}
+
+/// Error returned when a component of short channel ID is too large.
+///
+/// **Important: consumer code MUST NOT match on this using `OutOfRangeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct OutOfRangeError {
+    component: Component,
+    value: u32,
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the {} {} exceeds the maximum {}", self.component, self.value, self.component.max())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfRangeError {}
+
+/// Error returned when decoding raw bytes fails
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    len: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid length {} bytes, the length must be 8 bytes", self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Error returned when parsing text representation fails.
+///
+/// **Important: consumer code MUST NOT match on this using `ParseError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The string that was attempted to be parsed
+    #[cfg(feature = "alloc")]
+    input: String,
+    /// Information about what exactly went wrong
+    reason: ParseErrorInner,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_err!(f, "failed to parse{} short channel ID", opt_fmt!("alloc", format_args!(" '{}' as", &self.input)); &self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+/// Details about the error.
+///
+/// This is private to avoid committing to a representation.
+#[derive(Debug, Clone)]
+enum ParseErrorInner {
+    InvalidU64(core::num::ParseIntError),
+    MixedSeparators,
+    MissingComponent(Component),
+    TooManyComponents,
+    InvalidNumber(Component, core::num::ParseIntError),
+    OutOfRange(OutOfRangeError),
+}
+
+impl fmt::Display for ParseErrorInner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorInner::InvalidU64(error) => write_err!(f, "invalid integer"; error),
+            ParseErrorInner::MixedSeparators => f.write_str("both 'x' and ':' are used as separators"),
+            ParseErrorInner::MissingComponent(component) => write!(f, "missing {}", component),
+            ParseErrorInner::TooManyComponents => f.write_str("too many components (expected exactly 3)"),
+            ParseErrorInner::InvalidNumber(component, error) => write_err!(f, "invalid {}", component; error),
+            ParseErrorInner::OutOfRange(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorInner {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseErrorInner::InvalidU64(error) => Some(error),
+            ParseErrorInner::MixedSeparators => None,
+            ParseErrorInner::MissingComponent(_) => None,
+            ParseErrorInner::TooManyComponents => None,
+            ParseErrorInner::InvalidNumber(_, error) => Some(error),
+            ParseErrorInner::OutOfRange(_) => None,
+        }
+    }
+}
+
+impl From<OutOfRangeError> for ParseErrorInner {
+    fn from(value: OutOfRangeError) -> Self {
+        ParseErrorInner::OutOfRange(value)
+    }
+}
+
+/// Implementation of `parse_arg::ParseArg` trait
+#[cfg(feature = "parse_arg")]
+mod parse_arg_impl {
+    use core::fmt;
+    use super::ShortChannelId;
+
+    impl parse_arg::ParseArgFromStr for ShortChannelId {
+        fn describe_type<W: fmt::Write>(mut writer: W) -> fmt::Result {
+            writer.write_str("a short channel ID in the form BLOCKxTXxOUTPUT or a decimal integer")
+        }
+    }
+}
+
+/// Implementations of `serde` traits
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use core::convert::TryFrom;
+    use super::ShortChannelId;
+    use serde::{Serialize, Deserialize, Serializer, Deserializer, de::{Visitor, Error}};
+
+    struct ScidVisitor;
+
+    impl<'de> Visitor<'de> for ScidVisitor {
+        type Value = ShortChannelId;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a short channel ID in the form BLOCKxTXxOUTPUT or an unsigned integer")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> where E: Error {
+            ShortChannelId::parse_raw(v).map_err(|error| E::custom(format_args!("invalid short channel ID '{}': {}", v, error)))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> where E: Error {
+            Ok(ShortChannelId(v))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> where E: Error {
+            u64::try_from(v)
+                .map(ShortChannelId)
+                .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(v), &self))
+        }
+    }
+
+    /// `ShortChannelId` is serialized as `BLOCKxTXxOUTPUT` string to human-readable formats and
+    /// as `u64` to non-human-readable.
+    impl Serialize for ShortChannelId {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            if serializer.is_human_readable() {
+                serializer.collect_str(self)
+            } else {
+                serializer.serialize_u64(self.0)
+            }
+        }
+    }
+
+    /// `ShortChannelId` is deserialized from string or integer from human-readable formats and
+    /// from `u64` from non-human-readable.
+    impl<'de> Deserialize<'de> for ShortChannelId {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_any(ScidVisitor)
+            } else {
+                deserializer.deserialize_u64(ScidVisitor)
+            }
+        }
+    }
+}
+
+/// Implementations of `postgres-types` traits
+#[cfg(feature = "postgres-types")]
+mod postgres_impl {
+    use alloc::boxed::Box;
+    use super::ShortChannelId;
+    use postgres_types::{ToSql, FromSql, IsNull, Type};
+    use bytes::BytesMut;
+    use std::error::Error;
+
+    /// Stored as `BIGINT`
+    ///
+    /// The packed `u64` is reinterpreted as `i64` so identifiers with block height above
+    /// 8 388 607 are stored as negative numbers.
+    impl ToSql for ShortChannelId {
+        fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Send + Sync + 'static>> {
+            (self.0 as i64).to_sql(ty, out)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i64 as ToSql>::accepts(ty)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    /// Retrieved from `BIGINT`
+    ///
+    /// All values are valid since the packed `u64` is stored reinterpreted as `i64`.
+    impl<'a> FromSql<'a> for ShortChannelId {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Send + Sync + 'static>> {
+            Ok(ShortChannelId(<i64>::from_sql(ty, raw)? as u64))
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <i64 as FromSql>::accepts(ty)
+        }
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::ShortChannelId;
+    use slog::{Key, Value, Record, Serializer};
+
+    /// Uses `Display`
+    impl Value for ShortChannelId {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_arguments(key, &format_args!("{}", self))
+        }
+    }
+
+    impl_error_value!(super::ParseError, super::DecodeError, super::OutOfRangeError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShortChannelId;
+    use core::convert::TryFrom;
+    use alloc::string::ToString;
+
+    #[test]
+    fn packing() {
+        let scid = ShortChannelId::from_parts(0xabcdef, 0x123456, 0x789a).unwrap();
+        assert_eq!(scid.to_u64(), 0xabcd_ef12_3456_789a);
+        assert_eq!(scid.to_bytes(), [0xab, 0xcd, 0xef, 0x12, 0x34, 0x56, 0x78, 0x9a]);
+        assert_eq!(scid.block_height(), 0xabcdef);
+        assert_eq!(scid.tx_index(), 0x123456);
+        assert_eq!(scid.output_index(), 0x789a);
+        assert_eq!(ShortChannelId::try_from(&scid.to_bytes()[..]).unwrap(), scid);
+        assert!(ShortChannelId::try_from(&[0u8; 7][..]).is_err());
+    }
+
+    #[test]
+    fn max() {
+        let scid = ShortChannelId::from_u64(u64::MAX);
+        assert_eq!(scid.to_string(), "16777215x16777215x65535");
+        assert_eq!("16777215x16777215x65535".parse::<ShortChannelId>().unwrap(), scid);
+        assert!(ShortChannelId::from_parts(1 << 24, 0, 0).is_err());
+        assert!(ShortChannelId::from_parts(0, 1 << 24, 0).is_err());
+    }
+
+    #[test]
+    fn parse_formats() {
+        let expected = ShortChannelId::from_parts(700000, 1234, 0).unwrap();
+        assert_eq!("700000x1234x0".parse::<ShortChannelId>().unwrap(), expected);
+        assert_eq!("700000:1234:0".parse::<ShortChannelId>().unwrap(), expected);
+        assert_eq!(expected.to_u64().to_string().parse::<ShortChannelId>().unwrap(), expected);
+        assert_eq!(expected.to_string(), "700000x1234x0");
+    }
+
+    chk_err_impl! {
+        parse_scid_error_empty, "", ShortChannelId, [
+            "failed to parse '' as short channel ID",
+            "invalid integer",
+            "cannot parse integer from empty string",
+        ], [
+            "failed to parse short channel ID",
+            "invalid integer",
+            "cannot parse integer from empty string",
+        ];
+        parse_scid_error_mixed, "1x2:3", ShortChannelId, [
+            "failed to parse '1x2:3' as short channel ID",
+            "both 'x' and ':' are used as separators",
+        ], [
+            "failed to parse short channel ID",
+            "both 'x' and ':' are used as separators",
+        ];
+        parse_scid_error_missing, "1x2", ShortChannelId, [
+            "failed to parse '1x2' as short channel ID",
+            "missing output index",
+        ], [
+            "failed to parse short channel ID",
+            "missing output index",
+        ];
+        parse_scid_error_too_many, "1x2x3x4", ShortChannelId, [
+            "failed to parse '1x2x3x4' as short channel ID",
+            "too many components (expected exactly 3)",
+        ], [
+            "failed to parse short channel ID",
+            "too many components (expected exactly 3)",
+        ];
+        parse_scid_error_invalid_tx, "1xfoox3", ShortChannelId, [
+            "failed to parse '1xfoox3' as short channel ID",
+            "invalid transaction index",
+            "invalid digit found in string",
+        ], [
+            "failed to parse short channel ID",
+            "invalid transaction index",
+            "invalid digit found in string",
+        ];
+        parse_scid_error_block_range, "16777216x0x0", ShortChannelId, [
+            "failed to parse '16777216x0x0' as short channel ID",
+            "the block height 16777216 exceeds the maximum 16777215",
+        ], [
+            "failed to parse short channel ID",
+            "the block height 16777216 exceeds the maximum 16777215",
+        ];
+        parse_scid_error_output_range, "0:0:65536", ShortChannelId, [
+            "failed to parse '0:0:65536' as short channel ID",
+            "the output index 65536 exceeds the maximum 65535",
+        ], [
+            "failed to parse short channel ID",
+            "the output index 65536 exceeds the maximum 65535",
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let scid = ShortChannelId::from_parts(700000, 1234, 1).unwrap();
+        assert_eq!(serde_json::to_string(&scid).unwrap(), "\"700000x1234x1\"");
+        assert_eq!(serde_json::from_str::<ShortChannelId>("\"700000x1234x1\"").unwrap(), scid);
+        assert_eq!(serde_json::from_str::<ShortChannelId>("\"700000:1234:1\"").unwrap(), scid);
+        assert_eq!(serde_json::from_str::<ShortChannelId>("769658139524071425").unwrap(), scid);
+        assert!(serde_json::from_str::<ShortChannelId>("-1").is_err());
+
+        let bytes = bincode::serialize(&scid).unwrap();
+        assert_eq!(bytes, 769658139524071425u64.to_le_bytes());
+        assert_eq!(bincode::deserialize::<ShortChannelId>(&bytes).unwrap(), scid);
+    }
+}
@@ -0,0 +1,542 @@
+//! Encodings of integers used in Lightning Network messages
+//!
+//! This module implements `BigSize` and truncated integers (`tu16`, `tu32`, `tu64`) as defined in
+//! BOLT 1. They are mostly found in TLV records where `BigSize` encodes types and lengths and
+//! truncated integers encode values such as amounts.
+//!
+//! Both encodings require the value to be encoded minimally and decoding rejects non-minimal
+//! encodings with an error describing what exactly was wrong.
+//!
+//! Decoding works over byte slices without `std`, with `std` values can also be read from
+//! [`std::io::Read`] and written to [`std::io::Write`].
+//!
+//! ## Example
+//!
+//! ```
+//! use ln_types::Amount;
+//! use ln_types::wire::{BigSize, TruncatedInt};
+//!
+//! let mut bytes = &[0xfd, 0x01, 0x00, 0x03, 0xe8][..];
+//! assert_eq!(BigSize::decode(&mut bytes).unwrap(), BigSize(256));
+//! assert_eq!(Amount::decode_truncated(bytes).unwrap(), Amount::from_msat(1000).unwrap());
+//! assert_eq!(&*Amount::from_msat(1000).unwrap().encode_truncated(), &[0x03, 0xe8]);
+//! ```
+
+use core::fmt;
+use crate::amount::{Amount, OverflowError};
+
+#[cfg(feature = "std")]
+use std::io;
+
+/// Encoded value stored inline.
+///
+/// This is returned by encoding functions so that they don't need to allocate.
+/// Dereferences to the encoded bytes.
+#[derive(Copy, Clone)]
+pub struct Encoded {
+    bytes: [u8; 9],
+    len: u8,
+}
+
+impl Encoded {
+    /// Returns the encoded bytes.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+}
+
+impl core::ops::Deref for Encoded {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Encoded {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl core::borrow::Borrow<[u8]> for Encoded {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+/// Shows the bytes as a list
+impl fmt::Debug for Encoded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+/// Variable-length unsigned integer used in TLV streams.
+///
+/// Values below `0xfd` are encoded in a single byte, larger values are prefixed with `0xfd`,
+/// `0xfe` or `0xff` followed by 2, 4 or 8 big-endian bytes respectively. The shortest possible
+/// encoding must be used.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BigSize(pub u64);
+
+impl BigSize {
+    /// The maximum number of bytes used by the encoding.
+    pub const MAX_LEN: usize = 9;
+
+    /// Returns the number of bytes the minimal encoding of this value takes.
+    pub fn encoded_len(self) -> usize {
+        match self.0 {
+            0..=0xfc => 1,
+            0xfd..=0xffff => 3,
+            0x1_0000..=0xffff_ffff => 5,
+            _ => 9,
+        }
+    }
+
+    /// Encodes the value using minimal encoding.
+    pub fn encode(self) -> Encoded {
+        let mut bytes = [0; 9];
+        let len = self.encoded_len();
+        match len {
+            1 => bytes[0] = self.0 as u8,
+            3 => {
+                bytes[0] = 0xfd;
+                bytes[1..3].copy_from_slice(&(self.0 as u16).to_be_bytes());
+            },
+            5 => {
+                bytes[0] = 0xfe;
+                bytes[1..5].copy_from_slice(&(self.0 as u32).to_be_bytes());
+            },
+            _ => {
+                bytes[0] = 0xff;
+                bytes[1..9].copy_from_slice(&self.0.to_be_bytes());
+            },
+        }
+        Encoded {
+            bytes,
+            len: len as u8,
+        }
+    }
+
+    /// Decodes the value from the beginning of the slice and advances the slice past it.
+    ///
+    /// The slice is left unchanged if decoding fails.
+    pub fn decode(bytes: &mut &[u8]) -> Result<Self, DecodeError> {
+        let prefix = *bytes.first().ok_or(DecodeErrorInner::UnexpectedEnd { needed: 1, available: 0 })?;
+        let payload_len = Self::payload_len(prefix);
+        let payload = bytes.get(1..(1 + payload_len))
+            .ok_or(DecodeErrorInner::UnexpectedEnd { needed: 1 + payload_len, available: bytes.len() })?;
+        let value = Self::from_payload(prefix, payload)?;
+        *bytes = &bytes[(1 + payload_len)..];
+        Ok(value)
+    }
+
+    /// Reads the value from the reader.
+    ///
+    /// Exactly as many bytes as needed are read so it's a good idea to use a buffered reader.
+    #[cfg(feature = "std")]
+    pub fn read<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, ReadError> {
+        let mut bytes = [0; 9];
+        reader.read_exact(&mut bytes[..1])?;
+        let payload_len = Self::payload_len(bytes[0]);
+        reader.read_exact(&mut bytes[1..(1 + payload_len)])?;
+        Self::from_payload(bytes[0], &bytes[1..(1 + payload_len)]).map_err(Into::into)
+    }
+
+    /// Writes the minimal encoding of the value into the writer.
+    #[cfg(feature = "std")]
+    pub fn write<W: io::Write + ?Sized>(self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.encode())
+    }
+
+    /// Returns the number of bytes following the prefix.
+    fn payload_len(prefix: u8) -> usize {
+        match prefix {
+            0xfd => 2,
+            0xfe => 4,
+            0xff => 8,
+            _ => 0,
+        }
+    }
+
+    /// Decodes the value from the prefix and the bytes following it.
+    ///
+    /// The length of `payload` must be equal to `payload_len(prefix)`.
+    fn from_payload(prefix: u8, payload: &[u8]) -> Result<Self, DecodeErrorInner> {
+        let value = payload.iter().fold(0u64, |acc, byte| acc << 8 | u64::from(*byte));
+        let value = if payload.is_empty() { u64::from(prefix) } else { value };
+        let value = BigSize(value);
+        let len = 1 + payload.len();
+        if value.encoded_len() != len {
+            return Err(DecodeErrorInner::NonMinimalBigSize { value: value.0, len });
+        }
+        Ok(value)
+    }
+}
+
+impl From<u64> for BigSize {
+    #[inline]
+    fn from(value: u64) -> Self {
+        BigSize(value)
+    }
+}
+
+impl From<BigSize> for u64 {
+    #[inline]
+    fn from(value: BigSize) -> Self {
+        value.0
+    }
+}
+
+/// Private module preventing implementations of [`TruncatedInt`] outside of this crate.
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for crate::Amount {}
+}
+
+/// Truncated integer encoding (`tu16`, `tu32`, `tu64`).
+///
+/// The value is encoded in big-endian with all leading zero bytes omitted, so zero is encoded as
+/// an empty byte string. Since the encoding is not self-delimiting the length must be known from
+/// the context - usually it's the length of the TLV record.
+///
+/// This trait is sealed and implemented for `u16`, `u32`, `u64` and [`Amount`] (in millisatoshis
+/// as `tu64`).
+pub trait TruncatedInt: Sized + sealed::Sealed {
+    /// The maximum number of bytes used by the encoding.
+    const MAX_LEN: usize;
+
+    /// Encodes the value omitting leading zeros.
+    fn encode_truncated(&self) -> Encoded;
+
+    /// Decodes the value from the whole slice.
+    fn decode_truncated(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Reads `len` bytes from the reader and decodes them.
+    #[cfg(feature = "std")]
+    fn read_truncated<R: io::Read + ?Sized>(reader: &mut R, len: usize) -> Result<Self, ReadError> {
+        if len > Self::MAX_LEN {
+            return Err(DecodeError::from(DecodeErrorInner::TooLong { len, max: Self::MAX_LEN }).into());
+        }
+        // MAX_LEN is at most 8 for all (sealed) implementors
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes[..len])?;
+        Self::decode_truncated(&bytes[..len]).map_err(Into::into)
+    }
+
+    /// Writes the encoding of the value into the writer.
+    #[cfg(feature = "std")]
+    fn write_truncated<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.encode_truncated())
+    }
+}
+
+fn encode_truncated(value: u64) -> Encoded {
+    let len = 8 - value.leading_zeros() as usize / 8;
+    let mut bytes = [0; 9];
+    bytes[..len].copy_from_slice(&value.to_be_bytes()[(8 - len)..]);
+    Encoded {
+        bytes,
+        len: len as u8,
+    }
+}
+
+fn decode_truncated(bytes: &[u8], max: usize) -> Result<u64, DecodeErrorInner> {
+    if bytes.len() > max {
+        return Err(DecodeErrorInner::TooLong { len: bytes.len(), max });
+    }
+    if bytes.first() == Some(&0) {
+        return Err(DecodeErrorInner::LeadingZero { len: bytes.len() });
+    }
+    Ok(bytes.iter().fold(0u64, |acc, byte| acc << 8 | u64::from(*byte)))
+}
+
+macro_rules! impl_truncated_int {
+    ($($type:ty),*) => {
+        $(
+            impl TruncatedInt for $type {
+                const MAX_LEN: usize = core::mem::size_of::<$type>();
+
+                fn encode_truncated(&self) -> Encoded {
+                    encode_truncated((*self).into())
+                }
+
+                fn decode_truncated(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    // the length check guarantees the value fits
+                    Ok(decode_truncated(bytes, Self::MAX_LEN)? as $type)
+                }
+            }
+        )*
+    }
+}
+
+impl_truncated_int!(u16, u32, u64);
+
+/// Encoded as `tu64` millisatoshis
+impl TruncatedInt for Amount {
+    const MAX_LEN: usize = 8;
+
+    fn encode_truncated(&self) -> Encoded {
+        encode_truncated(self.to_msat())
+    }
+
+    fn decode_truncated(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let msat = decode_truncated(bytes, Self::MAX_LEN)?;
+        Amount::from_msat(msat).map_err(|error| DecodeErrorInner::Amount(error).into())
+    }
+}
+
+/// Error returned when decoding fails.
+///
+/// **Important: consumer code MUST NOT match on this using `DecodeError { .. }` syntax.
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    error: DecodeErrorInner,
+}
+
+impl From<DecodeErrorInner> for DecodeError {
+    fn from(value: DecodeErrorInner) -> Self {
+        DecodeError {
+            error: value,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.error {
+            DecodeErrorInner::UnexpectedEnd { needed, available } => write!(f, "unexpected end of input, {} bytes needed but only {} available", needed, available),
+            DecodeErrorInner::NonMinimalBigSize { value, len } => write!(f, "non-minimal BigSize encoding, the value {} is encoded using {} bytes but must use {}", value, len, BigSize(*value).encoded_len()),
+            DecodeErrorInner::TooLong { len, max } => write!(f, "the truncated integer is {} bytes long but the maximum is {} bytes", len, max),
+            DecodeErrorInner::LeadingZero { len } => write!(f, "non-minimal truncated integer encoding, the {}-byte value starts with a zero byte", len),
+            DecodeErrorInner::Amount(error) => write_err!(f, "invalid amount"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.error {
+            DecodeErrorInner::Amount(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum DecodeErrorInner {
+    UnexpectedEnd { needed: usize, available: usize },
+    NonMinimalBigSize { value: u64, len: usize },
+    TooLong { len: usize, max: usize },
+    LeadingZero { len: usize },
+    Amount(OverflowError),
+}
+
+/// Error returned when reading from [`std::io::Read`] fails.
+///
+/// It can be converted to [`std::io::Error`] with decoding errors having
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) kind.
+///
+/// **Important: consumer code MUST NOT match on this using `ReadError { .. }` syntax.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReadError {
+    error: ReadErrorInner,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+enum ReadErrorInner {
+    Io(io::Error),
+    Decode(DecodeError),
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ReadError {
+    fn from(value: io::Error) -> Self {
+        ReadError {
+            error: ReadErrorInner::Io(value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<DecodeError> for ReadError {
+    fn from(value: DecodeError) -> Self {
+        ReadError {
+            error: ReadErrorInner::Decode(value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<DecodeErrorInner> for ReadError {
+    fn from(value: DecodeErrorInner) -> Self {
+        DecodeError::from(value).into()
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ReadError> for io::Error {
+    fn from(value: ReadError) -> Self {
+        match value.error {
+            ReadErrorInner::Io(error) => error,
+            ReadErrorInner::Decode(error) => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.error {
+            ReadErrorInner::Io(error) => write_err!(f, "failed to read"; error),
+            ReadErrorInner::Decode(error) => write_err!(f, "failed to decode"; error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.error {
+            ReadErrorInner::Io(error) => Some(error),
+            ReadErrorInner::Decode(error) => Some(error),
+        }
+    }
+}
+
+/// Implementations of `slog` traits
+#[cfg(feature = "slog")]
+mod slog_impl {
+    use super::BigSize;
+    use slog::{Key, Value, Record, Serializer};
+
+    impl Value for BigSize {
+        fn serialize(&self, _rec: &Record, key: Key, serializer: &mut dyn Serializer) -> slog::Result {
+            serializer.emit_u64(key, self.0)
+        }
+    }
+
+    impl_error_value!(super::DecodeError);
+    #[cfg(feature = "std")]
+    impl_error_value!(super::ReadError);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BigSize, TruncatedInt};
+    use crate::Amount;
+    use alloc::string::{String, ToString};
+
+    /// Test vectors from BOLT 1 appendix A
+    const BIGSIZE_VECTORS: [(u64, &[u8]); 8] = [
+        (0, &[0x00]),
+        (252, &[0xfc]),
+        (253, &[0xfd, 0x00, 0xfd]),
+        (65535, &[0xfd, 0xff, 0xff]),
+        (65536, &[0xfe, 0x00, 0x01, 0x00, 0x00]),
+        (4294967295, &[0xfe, 0xff, 0xff, 0xff, 0xff]),
+        (4294967296, &[0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+        (18446744073709551615, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+    ];
+
+    fn bigsize_error(bytes: &[u8]) -> String {
+        let mut slice = bytes;
+        let error = BigSize::decode(&mut slice).unwrap_err();
+        assert_eq!(slice, bytes);
+        error.to_string()
+    }
+
+    #[test]
+    fn bigsize_roundtrip() {
+        for (value, bytes) in &BIGSIZE_VECTORS {
+            assert_eq!(&*BigSize(*value).encode(), *bytes);
+            assert_eq!(BigSize(*value).encoded_len(), bytes.len());
+            let mut slice = *bytes;
+            assert_eq!(BigSize::decode(&mut slice).unwrap(), BigSize(*value));
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn bigsize_decode_advances() {
+        let mut slice = &[0xfd, 0x00, 0xfd, 0x2a][..];
+        assert_eq!(BigSize::decode(&mut slice).unwrap(), BigSize(253));
+        assert_eq!(slice, [0x2a]);
+    }
+
+    #[test]
+    fn bigsize_errors() {
+        assert_eq!(bigsize_error(&[0xfd, 0x00, 0xfc]), "non-minimal BigSize encoding, the value 252 is encoded using 3 bytes but must use 1");
+        assert_eq!(bigsize_error(&[0xfe, 0x00, 0x00, 0xff, 0xff]), "non-minimal BigSize encoding, the value 65535 is encoded using 5 bytes but must use 3");
+        assert_eq!(bigsize_error(&[0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff]), "non-minimal BigSize encoding, the value 4294967295 is encoded using 9 bytes but must use 5");
+        assert_eq!(bigsize_error(&[0xfd, 0x00]), "unexpected end of input, 3 bytes needed but only 2 available");
+        assert_eq!(bigsize_error(&[0xff, 0xff, 0xff, 0xff, 0xff]), "unexpected end of input, 9 bytes needed but only 5 available");
+        assert_eq!(bigsize_error(&[]), "unexpected end of input, 1 bytes needed but only 0 available");
+    }
+
+    #[test]
+    fn truncated_roundtrip() {
+        for value in [0u64, 1, 0xff, 0x100, 0xffff, 0x1_0000, u64::MAX].iter() {
+            let encoded = value.encode_truncated();
+            assert_eq!(encoded.len(), 8 - value.leading_zeros() as usize / 8);
+            assert_eq!(u64::decode_truncated(&encoded).unwrap(), *value);
+        }
+        assert!(0u16.encode_truncated().is_empty());
+        assert_eq!(&*0x1234u16.encode_truncated(), &[0x12, 0x34]);
+        assert_eq!(&*0x10000u32.encode_truncated(), &[0x01, 0x00, 0x00]);
+        assert_eq!(u32::decode_truncated(&[0xff, 0xff, 0xff, 0xff]).unwrap(), u32::MAX);
+    }
+
+    #[test]
+    fn truncated_errors() {
+        assert_eq!(u64::decode_truncated(&[0x00]).unwrap_err().to_string(), "non-minimal truncated integer encoding, the 1-byte value starts with a zero byte");
+        assert_eq!(u32::decode_truncated(&[0x00, 0x01]).unwrap_err().to_string(), "non-minimal truncated integer encoding, the 2-byte value starts with a zero byte");
+        assert_eq!(u16::decode_truncated(&[0x01, 0x00, 0x00]).unwrap_err().to_string(), "the truncated integer is 3 bytes long but the maximum is 2 bytes");
+        assert_eq!(u64::decode_truncated(&[0x01; 9]).unwrap_err().to_string(), "the truncated integer is 9 bytes long but the maximum is 8 bytes");
+    }
+
+    #[test]
+    fn amount() {
+        let amount = Amount::from_msat(1000).unwrap();
+        assert_eq!(&*amount.encode_truncated(), &[0x03, 0xe8]);
+        assert_eq!(Amount::decode_truncated(&[0x03, 0xe8]).unwrap(), amount);
+        assert_eq!(Amount::decode_truncated(&[]).unwrap(), Amount::ZERO);
+        let error = Amount::decode_truncated(&[0xff; 8]).unwrap_err();
+        #[cfg(feature = "std")]
+        assert_eq!(error.to_string(), "invalid amount");
+        #[cfg(not(feature = "std"))]
+        assert_eq!(error.to_string(), "invalid amount: 18446744073709551615 millisatoshis exceeds the maximum number of 21 million bitcoins");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn io() {
+        let mut buf = alloc::vec::Vec::new();
+        BigSize(65536).write(&mut buf).unwrap();
+        Amount::from_msat(1000).unwrap().write_truncated(&mut buf).unwrap();
+        assert_eq!(buf, [0xfe, 0x00, 0x01, 0x00, 0x00, 0x03, 0xe8]);
+
+        let mut reader = &buf[..];
+        assert_eq!(BigSize::read(&mut reader).unwrap(), BigSize(65536));
+        assert_eq!(Amount::read_truncated(&mut reader, 2).unwrap(), Amount::from_msat(1000).unwrap());
+        assert!(reader.is_empty());
+
+        let error = BigSize::read(&mut &[0xfd, 0x00, 0x01][..]).unwrap_err();
+        assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::InvalidData);
+        let error = BigSize::read(&mut &[0xfd, 0x01][..]).unwrap_err();
+        assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(u16::read_truncated(&mut &[0x01, 0x02, 0x03][..], 3).is_err());
+    }
+}